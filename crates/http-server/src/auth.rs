@@ -0,0 +1,225 @@
+//! API keys with roles, checked on routes that change the shared model.
+//!
+//! Keys are sent as `Authorization: Bearer <key>` or `X-API-Key: <key>`.
+//! Missing or unknown keys get 401, known keys with too small a role 403.
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::str::FromStr;
+
+use crate::handlers::error::ApiError;
+use crate::state::AppState;
+
+/// Ordered by privilege: an admin may do everything a trainer may.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Trainer,
+    Admin,
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "trainer" => Ok(Role::Trainer),
+            "admin" => Ok(Role::Admin),
+            _ => Err(format!("unknown role {:?}", s)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ApiKeys {
+    keys: Vec<(String, Role)>,
+}
+
+impl ApiKeys {
+    /// Parses `role:key` pairs separated by commas, e.g.
+    /// `admin:s3cret,trainer:t0ken`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let keys = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (role, key) = entry
+                    .split_once(':')
+                    .ok_or_else(|| format!("expected role:key, got {:?}", entry))?;
+                if key.is_empty() {
+                    return Err(format!("empty key for role {:?}", role));
+                }
+                Ok((key.to_string(), role.parse()?))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { keys })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Compares against every key in constant time per key, so response
+    /// timing doesn't reveal how much of a guess was right.
+    pub fn role(&self, key: &str) -> Option<Role> {
+        self.keys.iter().fold(None, |found, (candidate, role)| {
+            if constant_time_eq(candidate.as_bytes(), key.as_bytes()) {
+                found.max(Some(*role))
+            } else {
+                found
+            }
+        })
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+pub(crate) fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    bearer
+        .or_else(|| headers.get("x-api-key")?.to_str().ok())
+        .map(str::trim)
+}
+
+async fn authorize(state: &AppState, request: Request, next: Next, required: Role) -> Response {
+    let role = presented_key(request.headers()).and_then(|key| state.auth.role(key));
+
+    match role {
+        Some(role) if role >= required => next.run(request).await,
+        Some(_) => {
+            ApiError::new(StatusCode::FORBIDDEN, "This key may not use this route").into_response()
+        }
+        None => (
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            ApiError::new(StatusCode::UNAUTHORIZED, "A valid API key is required"),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn require_trainer(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    authorize(&state, request, next, Role::Trainer).await
+}
+
+pub async fn require_admin(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    authorize(&state, request, next, Role::Admin).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+    use axum::body::Body;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    #[test]
+    fn parses_roles_and_looks_up_keys() {
+        let keys = ApiKeys::parse(" admin:a1, trainer:t1 ,").unwrap();
+
+        assert_eq!(keys.role("a1"), Some(Role::Admin));
+        assert_eq!(keys.role("t1"), Some(Role::Trainer));
+        assert_eq!(keys.role("t"), None);
+        assert!(ApiKeys::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn presented_keys_are_trimmed() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer  t1 ".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("t1"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", " a1".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("a1"));
+    }
+
+    #[test]
+    fn rejects_malformed_specs() {
+        assert!(ApiKeys::parse("admin").is_err());
+        assert!(ApiKeys::parse("root:x").is_err());
+        assert!(ApiKeys::parse("trainer:").is_err());
+    }
+
+    #[tokio::test]
+    async fn training_requires_a_trainer_or_admin_key() {
+        let app = app().await;
+        let sample = json!({ "label": 1, "image": vec![0u8; 784] });
+
+        for (key, expected) in [
+            (None, StatusCode::UNAUTHORIZED),
+            (Some("wrong-key"), StatusCode::UNAUTHORIZED),
+            (Some("trainer-key"), StatusCode::OK),
+            (Some("admin-key"), StatusCode::OK),
+        ] {
+            let (status, _) = post_with_key(app.clone(), "/api/train", key, sample.clone()).await;
+            assert_eq!(status, expected, "{key:?}");
+        }
+
+        // X-API-Key works as well as a bearer token.
+        let request = Request::post("/api/train")
+            .header("content-type", "application/json")
+            .header("x-api-key", "trainer-key")
+            .body(Body::from(sample.to_string()))
+            .unwrap();
+        assert_eq!(app.oneshot(request).await.unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn model_management_requires_admin() {
+        let app = app().await;
+
+        let (status, _) = post_with_key(
+            app.clone(),
+            "/api/model/reload",
+            Some("trainer-key"),
+            json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = post_with_key(
+            app.clone(),
+            "/api/model/reload",
+            Some("admin-key"),
+            json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["training_steps"], 0);
+
+        let (status, _) = post_with_key(app, "/api/model/save", None, json!({})).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn prediction_stays_public() {
+        let (status, _) = post(
+            app().await,
+            "/api/predict",
+            json!({ "image": vec![0u8; 784] }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+    }
+}
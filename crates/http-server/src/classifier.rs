@@ -1 +1,308 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
 
+use nn_engine::port::async_classifier::{
+    AsyncBatchPredictor, AsyncDigitPredictor, AsyncDigitTrainer,
+};
+use nn_engine::{NNError, Prediction, TrainingStepResult};
+
+#[derive(Clone, Debug)]
+pub struct BatchConfig {
+    /// Largest number of requests merged into one forward pass.
+    pub max_batch_size: usize,
+    /// How long the first request of a batch may wait for company.
+    pub max_wait: Duration,
+    /// Requests buffered before `predict` starts applying backpressure.
+    pub queue_capacity: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 32,
+            max_wait: Duration::from_millis(2),
+            queue_capacity: 1024,
+        }
+    }
+}
+
+/// Upper bounds of the batch size histogram buckets.
+const BATCH_SIZE_BUCKETS: [u64; 7] = [1, 2, 4, 8, 16, 32, 64];
+
+#[derive(Default)]
+pub struct BatchMetrics {
+    batches: AtomicU64,
+    requests: AtomicU64,
+    max_batch_size: AtomicU64,
+    queue_latency_us_total: AtomicU64,
+    queue_latency_us_max: AtomicU64,
+    batch_size_buckets: [AtomicU64; BATCH_SIZE_BUCKETS.len() + 1],
+}
+
+#[derive(Serialize)]
+pub struct BatchMetricsSnapshot {
+    pub batches: u64,
+    pub requests: u64,
+    pub mean_batch_size: f64,
+    pub max_batch_size: u64,
+    pub mean_queue_latency_ms: f64,
+    pub max_queue_latency_ms: f64,
+    /// `(upper bound, count)` pairs; the last bucket has no upper bound.
+    pub batch_size_histogram: Vec<(Option<u64>, u64)>,
+}
+
+impl BatchMetrics {
+    fn record_batch(&self, size: usize, latencies: impl Iterator<Item = Duration>) {
+        let size = size as u64;
+
+        self.batches.fetch_add(1, Ordering::Relaxed);
+        self.requests.fetch_add(size, Ordering::Relaxed);
+        self.max_batch_size.fetch_max(size, Ordering::Relaxed);
+
+        let bucket = BATCH_SIZE_BUCKETS
+            .iter()
+            .position(|bound| size <= *bound)
+            .unwrap_or(BATCH_SIZE_BUCKETS.len());
+        self.batch_size_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+
+        for latency in latencies {
+            let us = latency.as_micros() as u64;
+            self.queue_latency_us_total.fetch_add(us, Ordering::Relaxed);
+            self.queue_latency_us_max.fetch_max(us, Ordering::Relaxed);
+        }
+    }
+
+    pub fn snapshot(&self) -> BatchMetricsSnapshot {
+        let batches = self.batches.load(Ordering::Relaxed);
+        let requests = self.requests.load(Ordering::Relaxed);
+        let latency_total = self.queue_latency_us_total.load(Ordering::Relaxed);
+
+        let bounds = BATCH_SIZE_BUCKETS.iter().map(|b| Some(*b)).chain([None]);
+
+        BatchMetricsSnapshot {
+            batches,
+            requests,
+            mean_batch_size: requests as f64 / batches.max(1) as f64,
+            max_batch_size: self.max_batch_size.load(Ordering::Relaxed),
+            mean_queue_latency_ms: latency_total as f64 / requests.max(1) as f64 / 1000.0,
+            max_queue_latency_ms: self.queue_latency_us_max.load(Ordering::Relaxed) as f64 / 1000.0,
+            batch_size_histogram: bounds
+                .zip(self.batch_size_buckets.iter())
+                .map(|(bound, count)| (bound, count.load(Ordering::Relaxed)))
+                .collect(),
+        }
+    }
+}
+
+struct PendingPrediction {
+    pixels: Vec<u8>,
+    enqueued: Instant,
+    reply: oneshot::Sender<Result<Prediction, NNError>>,
+}
+
+/// Micro-batching layer in front of a classifier.
+///
+/// Concurrent `predict` calls are queued and merged into a single
+/// `predict_batch` call once `max_batch_size` requests are waiting or the
+/// oldest one has waited `max_wait`. Training is passed straight through.
+pub struct BatchingClassifier<C> {
+    inner: Arc<C>,
+    queue: mpsc::Sender<PendingPrediction>,
+    metrics: Arc<BatchMetrics>,
+}
+
+impl<C> BatchingClassifier<C>
+where
+    C: AsyncBatchPredictor + Send + Sync + 'static,
+{
+    pub fn new(inner: Arc<C>, config: BatchConfig) -> Self {
+        let (queue, receiver) = mpsc::channel(config.queue_capacity.max(1));
+        let metrics = Arc::new(BatchMetrics::default());
+
+        tokio::spawn(run_batcher(
+            inner.clone(),
+            receiver,
+            config,
+            metrics.clone(),
+        ));
+
+        Self {
+            inner,
+            queue,
+            metrics,
+        }
+    }
+
+    pub fn metrics(&self) -> Arc<BatchMetrics> {
+        self.metrics.clone()
+    }
+}
+
+async fn run_batcher<C>(
+    inner: Arc<C>,
+    mut receiver: mpsc::Receiver<PendingPrediction>,
+    config: BatchConfig,
+    metrics: Arc<BatchMetrics>,
+) where
+    C: AsyncBatchPredictor + Send + Sync + 'static,
+{
+    while let Some(first) = receiver.recv().await {
+        let deadline = first.enqueued + config.max_wait;
+        let mut batch = vec![first];
+
+        while batch.len() < config.max_batch_size {
+            match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(pending)) => batch.push(pending),
+                _ => break,
+            }
+        }
+
+        let now = Instant::now();
+        metrics.record_batch(batch.len(), batch.iter().map(|p| now - p.enqueued));
+        tracing::debug!("Dispatching prediction batch of {} requests", batch.len());
+
+        tokio::spawn(dispatch(inner.clone(), batch));
+    }
+}
+
+async fn dispatch<C>(inner: Arc<C>, batch: Vec<PendingPrediction>)
+where
+    C: AsyncBatchPredictor + Send + Sync + 'static,
+{
+    let (inputs, replies): (Vec<_>, Vec<_>) =
+        batch.into_iter().map(|p| (p.pixels, p.reply)).unzip();
+
+    match inner.predict_batch(inputs).await {
+        Ok(results) => {
+            for (reply, result) in replies.into_iter().zip(results) {
+                let _ = reply.send(result);
+            }
+        }
+        Err(e) => {
+            tracing::error!("Batched prediction failed: {:?}", e);
+            for reply in replies {
+                let _ = reply.send(Err(e.clone()));
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<C> AsyncDigitPredictor for BatchingClassifier<C>
+where
+    C: AsyncBatchPredictor + Send + Sync + 'static,
+{
+    async fn predict(&self, pixels: &[u8]) -> Result<Prediction, NNError> {
+        let (reply, response) = oneshot::channel();
+
+        self.queue
+            .send(PendingPrediction {
+                pixels: pixels.to_vec(),
+                enqueued: Instant::now(),
+                reply,
+            })
+            .await
+            .map_err(|_| NNError::InternalError)?;
+
+        response.await.map_err(|_| NNError::InternalError)?
+    }
+}
+
+#[async_trait]
+impl<C> AsyncDigitTrainer for BatchingClassifier<C>
+where
+    C: AsyncBatchPredictor + AsyncDigitTrainer + Send + Sync + 'static,
+{
+    async fn train(&self, label: u8, pixels: &[u8]) -> Result<TrainingStepResult, NNError> {
+        self.inner.train(label, pixels).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingPredictor {
+        batch_sizes: Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl AsyncBatchPredictor for RecordingPredictor {
+        async fn predict_batch(
+            &self,
+            batch: Vec<Vec<u8>>,
+        ) -> Result<Vec<Result<Prediction, NNError>>, NNError> {
+            self.batch_sizes.lock().unwrap().push(batch.len());
+
+            Ok(batch
+                .iter()
+                .map(|pixels| match pixels.first() {
+                    Some(digit) => Ok(Prediction {
+                        digit: *digit,
+                        confidence: 1.0,
+                    }),
+                    None => Err(NNError::InvalidInput),
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_are_merged_and_fanned_out() {
+        let inner = Arc::new(RecordingPredictor::default());
+        let batcher = Arc::new(BatchingClassifier::new(
+            inner.clone(),
+            BatchConfig {
+                max_batch_size: 8,
+                max_wait: Duration::from_millis(200),
+                queue_capacity: 16,
+            },
+        ));
+
+        let handles: Vec<_> = (0..8u8)
+            .map(|digit| {
+                let batcher = batcher.clone();
+                tokio::spawn(async move { (digit, batcher.predict(&[digit]).await) })
+            })
+            .collect();
+
+        for handle in handles {
+            let (digit, result) = handle.await.unwrap();
+            assert_eq!(result.unwrap().digit, digit);
+        }
+
+        let sizes = inner.batch_sizes.lock().unwrap().clone();
+        assert_eq!(sizes.iter().sum::<usize>(), 8);
+        assert!(sizes.len() < 8);
+
+        let snapshot = batcher.metrics().snapshot();
+        assert_eq!(snapshot.requests, 8);
+        assert_eq!(snapshot.batches, sizes.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn lone_request_is_flushed_after_max_wait() {
+        let inner = Arc::new(RecordingPredictor::default());
+        let batcher = BatchingClassifier::new(
+            inner.clone(),
+            BatchConfig {
+                max_batch_size: 32,
+                max_wait: Duration::from_millis(5),
+                queue_capacity: 16,
+            },
+        );
+
+        assert!(matches!(
+            batcher.predict(&[]).await,
+            Err(NNError::InvalidInput)
+        ));
+        assert_eq!(*inner.batch_sizes.lock().unwrap(), vec![1]);
+    }
+}
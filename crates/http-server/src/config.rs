@@ -0,0 +1,123 @@
+use std::env;
+use std::time::Duration;
+
+use crate::auth::ApiKeys;
+use crate::classifier::BatchConfig;
+use crate::limits::{LimitsConfig, RateLimit};
+
+pub struct ServerConfig {
+    pub addr: String,
+    pub model_path: String,
+    pub batching: BatchConfig,
+    /// Keys allowed to train and manage the model; none means nobody may.
+    pub api_keys: ApiKeys,
+    pub limits: LimitsConfig,
+    /// Append-only log backing the feedback review queue.
+    pub feedback_path: String,
+    pub feedback_flag_confidence: f32,
+    /// Append-only dataset of the samples online training steps were
+    /// accepted on; rejected updates aren't recorded.
+    pub samples_path: String,
+    /// `None` trains on submitted samples alone.
+    pub replay: Option<ReplayConfig>,
+}
+
+/// Rehearsal data for online training, read from an MNIST CSV.
+pub struct ReplayConfig {
+    pub path: String,
+    /// Rows mixed into online updates.
+    pub samples: usize,
+    /// Rehearsal samples trained with each submitted sample.
+    pub ratio: usize,
+    /// Rows after the rehearsal ones held out to validate updates.
+    pub validation_samples: usize,
+    /// Updates leaving validation accuracy below this are rolled back.
+    pub min_accuracy: Option<f32>,
+}
+
+impl ServerConfig {
+    /// Reads `MNIST_*` environment variables, falling back to the defaults.
+    pub fn from_env() -> Self {
+        let batching = BatchConfig::default();
+        let limits = LimitsConfig::default();
+        let burst = env_parse("MNIST_RATE_LIMIT_BURST");
+
+        Self {
+            addr: env::var("MNIST_ADDR").unwrap_or_else(|_| "127.0.0.1:3000".to_string()),
+            model_path: env::var("MNIST_MODEL_PATH").unwrap_or_else(|_| {
+                format!(
+                    "{}/../../assets/models/default.bin",
+                    env!("CARGO_MANIFEST_DIR")
+                )
+            }),
+            feedback_path: env::var("MNIST_FEEDBACK_PATH").unwrap_or_else(|_| {
+                format!(
+                    "{}/../../assets/feedback/queue.jsonl",
+                    env!("CARGO_MANIFEST_DIR")
+                )
+            }),
+            samples_path: env::var("MNIST_SAMPLES_PATH").unwrap_or_else(|_| {
+                format!(
+                    "{}/../../assets/feedback/samples.jsonl",
+                    env!("CARGO_MANIFEST_DIR")
+                )
+            }),
+            feedback_flag_confidence: env_parse("MNIST_FEEDBACK_FLAG_CONFIDENCE").unwrap_or(0.9),
+            replay: env::var("MNIST_REPLAY_PATH").ok().map(|path| ReplayConfig {
+                path,
+                samples: env_parse("MNIST_REPLAY_SAMPLES").unwrap_or(2000),
+                ratio: env_parse("MNIST_REPLAY_RATIO").unwrap_or(8),
+                validation_samples: env_parse("MNIST_VALIDATION_SAMPLES").unwrap_or(500),
+                min_accuracy: env_parse("MNIST_MIN_VALIDATION_ACCURACY"),
+            }),
+            batching: BatchConfig {
+                max_batch_size: env_parse("MNIST_BATCH_MAX_SIZE")
+                    .unwrap_or(batching.max_batch_size),
+                max_wait: env_parse("MNIST_BATCH_MAX_WAIT_MS")
+                    .map(Duration::from_millis)
+                    .unwrap_or(batching.max_wait),
+                queue_capacity: env_parse("MNIST_BATCH_QUEUE_CAPACITY")
+                    .unwrap_or(batching.queue_capacity),
+            },
+            api_keys: match ApiKeys::parse(&env::var("MNIST_API_KEYS").unwrap_or_default()) {
+                Ok(keys) => keys,
+                Err(e) => {
+                    tracing::error!("Ignoring invalid MNIST_API_KEYS: {}", e);
+                    ApiKeys::default()
+                }
+            },
+            limits: LimitsConfig {
+                max_body_bytes: env_parse("MNIST_MAX_BODY_BYTES").unwrap_or(limits.max_body_bytes),
+                request_timeout: env_parse("MNIST_REQUEST_TIMEOUT_MS")
+                    .map(Duration::from_millis)
+                    .unwrap_or(limits.request_timeout),
+                max_concurrency: env_parse("MNIST_MAX_CONCURRENCY")
+                    .unwrap_or(limits.max_concurrency),
+                // A rate of 0 turns rate limiting off.
+                rate_limit: match env_parse::<f64>("MNIST_RATE_LIMIT_RPS") {
+                    Some(per_second) if per_second <= 0.0 => None,
+                    Some(per_second) => Some(RateLimit {
+                        per_second,
+                        burst: burst.unwrap_or(2.0 * per_second),
+                    }),
+                    None => limits.rate_limit.map(|rate| RateLimit {
+                        burst: burst.unwrap_or(rate.burst),
+                        ..rate
+                    }),
+                },
+            },
+        }
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let value = env::var(name).ok()?;
+
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            tracing::warn!("Ignoring invalid value for {}: {:?}", name, value);
+            None
+        }
+    }
+}
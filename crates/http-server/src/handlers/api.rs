@@ -1,13 +1,29 @@
+use crate::handlers::error::ApiError;
 use crate::state::AppState;
+use crate::upload::{ImageUpload, decode_grayscale};
 use axum::{
-    extract::{Json, State},
+    extract::{Json, Query, State},
+    http::StatusCode,
     response::IntoResponse,
 };
+use nn_engine::port::async_classifier::{AsyncBatchPredictor, AsyncDigitPredictor};
+use nn_engine::{
+    GrayImage, MNIST_SIDE, NNError, PreprocessOptions, Recognition, SubmittedSample,
+    TrainingStepResult, preprocess, segment_digits,
+};
 use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Deserialize)]
 pub struct PredictRequest {
-    pub image: Vec<u8>, // 784 пикселя
+    pub image: Vec<u8>, // 784 пикселя или width * height при preprocess
+    #[serde(default)]
+    pub width: Option<usize>,
+    #[serde(default)]
+    pub height: Option<usize>,
+    /// MNIST-style preprocessing; without it the image is used as-is.
+    #[serde(default)]
+    pub preprocess: Option<PreprocessOptions>,
 }
 
 #[derive(Serialize)]
@@ -16,17 +32,45 @@ pub struct PredictResponse {
     pub confidence: f32,
 }
 
+/// Runs the requested preprocessing off the async workers, or passes raw
+/// 28×28 pixels through.
+pub(crate) async fn prepare_image(
+    image: Vec<u8>,
+    width: Option<usize>,
+    height: Option<usize>,
+    options: Option<PreprocessOptions>,
+) -> Result<Vec<u8>, ApiError> {
+    let Some(options) = options else {
+        return Ok(image);
+    };
+    let image = GrayImage::new(
+        width.unwrap_or(MNIST_SIDE),
+        height.unwrap_or(MNIST_SIDE),
+        image,
+    )?;
+
+    tokio::task::spawn_blocking(move || preprocess(&image, &options))
+        .await
+        .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
 pub async fn predict(
     State(state): State<AppState>,
     Json(payload): Json<PredictRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     tracing::debug!(
         "Received predict request with image of length: {}",
         payload.image.len()
     );
 
-    // ToDo - Правильно обрабатывать ошибки, а не просто unwrap
-    let result = state.classifier.predict(&payload.image).await.unwrap();
+    let image = prepare_image(
+        payload.image,
+        payload.width,
+        payload.height,
+        payload.preprocess,
+    )
+    .await?;
+    let result = state.classifier.predict(&image).await?;
 
     tracing::debug!(
         "Prediction result: digit={}, confidence={}",
@@ -34,34 +78,392 @@ pub async fn predict(
         result.confidence
     );
 
-    Json(PredictResponse {
+    Ok(Json(PredictResponse {
         digit: result.digit,
         confidence: result.confidence,
+    }))
+}
+
+/// Predicts from an uploaded PNG/JPEG/BMP (multipart or raw body); the
+/// query string carries `PreprocessOptions`, e.g. `?deskew=true`.
+pub async fn predict_image(
+    State(state): State<AppState>,
+    Query(options): Query<PreprocessOptions>,
+    ImageUpload(bytes): ImageUpload,
+) -> Result<Json<PredictResponse>, ApiError> {
+    tracing::debug!("Received image upload of {} bytes", bytes.len());
+
+    let image = tokio::task::spawn_blocking(move || {
+        decode_grayscale(&bytes).map(|image| preprocess(&image, &options))
     })
+    .await
+    .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))??;
+
+    let result = state.classifier.predict(&image).await?;
+
+    Ok(Json(PredictResponse {
+        digit: result.digit,
+        confidence: result.confidence,
+    }))
 }
 
 #[derive(Deserialize)]
 pub struct TrainRequest {
     pub label: u8,
     pub image: Vec<u8>,
+    #[serde(default)]
+    pub width: Option<usize>,
+    #[serde(default)]
+    pub height: Option<usize>,
+    #[serde(default)]
+    pub preprocess: Option<PreprocessOptions>,
 }
 
 pub async fn train(
     State(state): State<AppState>,
     Json(payload): Json<TrainRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, ApiError> {
     tracing::debug!(
         "Received train request with image of length: {}, label: {}",
         payload.image.len(),
         payload.label
     );
 
-    let result = state.classifier.train(payload.label, &payload.image).await;
+    let image = prepare_image(
+        payload.image,
+        payload.width,
+        payload.height,
+        payload.preprocess,
+    )
+    .await?;
+    let result = train_and_record(&state, payload.label, image).await;
 
-    match result {
+    match &result {
         Ok(_) => tracing::debug!("Training successful for label: {}", payload.label),
         Err(e) => tracing::error!("Training failed for label: {}: {:?}", payload.label, e),
     }
+    result?;
+
+    Ok(Json("ok"))
+}
+
+/// Stores the sample with the served model's version and prediction, then
+/// trains on it; a sample that can't be stored isn't trained on.
+pub(crate) async fn train_and_record(
+    state: &AppState,
+    label: u8,
+    pixels: Vec<u8>,
+) -> Result<TrainingStepResult, ApiError> {
+    if label > 9 {
+        return Err(NNError::InvalidInput.into());
+    }
+
+    // Straight to the model, so the extra forward pass stays out of the
+    // prediction metrics.
+    let prediction = state.model.predict(&pixels).await?;
+    let sample = SubmittedSample {
+        label,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0.0, |d| d.as_secs_f64()),
+        model_version: state.model.version().unwrap_or_default(),
+        prediction,
+        pixels,
+    };
+    let step = state.classifier.train(label, &sample.pixels).await?;
+
+    // Only accepted steps are recorded; the model already changed, so a
+    // failed write is logged rather than reported as a failed step.
+    if let Err(e) = state.samples.append(&sample).await {
+        tracing::error!("Failed to record training sample: {}", e);
+    }
+
+    Ok(step)
+}
+
+#[derive(Deserialize)]
+pub struct RecognizeRequest {
+    pub image: Vec<u8>,
+    pub width: usize,
+    pub height: usize,
+    #[serde(default)]
+    pub preprocess: PreprocessOptions,
+}
+
+/// Reads a multi-digit number: segments the image and classifies each digit.
+pub async fn recognize(
+    State(state): State<AppState>,
+    Json(payload): Json<RecognizeRequest>,
+) -> Result<Json<Recognition>, ApiError> {
+    let image = GrayImage::new(payload.width, payload.height, payload.image)?;
+    let options = payload.preprocess;
+
+    let segments = tokio::task::spawn_blocking(move || segment_digits(&image, &options))
+        .await
+        .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))??;
+
+    // All digits in one batch rather than one queued request each.
+    let pixels = segments.iter().map(|s| s.pixels.clone()).collect();
+    let predictions = state
+        .model
+        .predict_batch(pixels)
+        .await?
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    for prediction in &predictions {
+        state.metrics.record_prediction(prediction);
+    }
+
+    tracing::debug!("Recognized {} digits", segments.len());
+
+    Ok(Json(Recognition::new(&segments, predictions)))
+}
+
+pub async fn batching_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.batching.snapshot())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::limits::LimitsConfig;
+    use crate::routes::router;
+    use crate::test_support::*;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use nn_engine::JsonLinesSampleStore;
+    use nn_engine::port::sample_store::SampleStore;
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn predict_preprocesses_full_canvas() {
+        let canvas: Vec<u8> = (0..280 * 280)
+            .map(|i| {
+                if (120..160).contains(&(i % 280)) {
+                    255
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        let (status, body) = post(
+            app().await,
+            "/api/predict",
+            json!({ "image": canvas, "width": 280, "height": 280, "preprocess": {} }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["digit"].as_u64().unwrap() < 10);
+    }
+
+    #[tokio::test]
+    async fn predict_rejects_wrong_image_size() {
+        let (status, body) = post(app().await, "/api/predict", json!({ "image": [0, 1, 2] })).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].is_string());
 
-    Json("ok")
+        let overflowing = json!({
+            "image": [],
+            "width": 4_294_967_296u64,
+            "height": 4_294_967_296u64,
+            "preprocess": {}
+        });
+        let (status, _) = post(app().await, "/api/predict", overflowing).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    fn png(invert: bool) -> Vec<u8> {
+        let image = image::GrayImage::from_fn(120, 160, |x, y| {
+            let ink = (50..70).contains(&x) && (20..140).contains(&y);
+            image::Luma([if ink != invert { 255 } else { 0 }])
+        });
+
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    async fn upload(app: Router, content_type: &str, body: Vec<u8>) -> (StatusCode, Value) {
+        let request = Request::post("/api/predict/image?deskew=true")
+            .header("content-type", content_type)
+            .body(Body::from(body))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn predict_image_accepts_raw_and_multipart_uploads() {
+        let (status, raw) = upload(app().await, "image/png", png(false)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let mut multipart = b"--XYZ\r\nContent-Disposition: form-data; name=\"image\"; filename=\"d.png\"\r\nContent-Type: image/png\r\n\r\n".to_vec();
+        multipart.extend(png(true));
+        multipart.extend(b"\r\n--XYZ--\r\n");
+
+        let (status, body) =
+            upload(app().await, "multipart/form-data; boundary=XYZ", multipart).await;
+        assert_eq!(status, StatusCode::OK);
+        // Dark ink on white paper is inverted to the same MNIST-like input.
+        assert_eq!(body, raw);
+    }
+
+    #[tokio::test]
+    async fn predict_image_rejects_unknown_format() {
+        let (status, body) = upload(app().await, "image/png", b"not an image".to_vec()).await;
+
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert!(body["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn predict_image_rejects_oversized_images() {
+        let image = image::GrayImage::new(nn_engine::MAX_IMAGE_SIDE as u32 + 1, 1);
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+
+        let (status, _) = upload(app().await, "image/png", bytes.into_inner()).await;
+
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn recognize_reads_every_digit() {
+        let (width, height) = (300, 80);
+        let canvas: Vec<u8> = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                let ink = (10..70).contains(&y)
+                    && [30, 130, 230].iter().any(|x0| (*x0..x0 + 12).contains(&x));
+                if ink { 255 } else { 0 }
+            })
+            .collect();
+
+        let (status, body) = post(
+            app().await,
+            "/api/recognize",
+            json!({ "image": canvas, "width": width, "height": height }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["text"].as_str().unwrap().len(), 3);
+        let xs: Vec<u64> = body["digits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["bounds"]["x"].as_u64().unwrap())
+            .collect();
+        assert_eq!(xs, vec![30, 130, 230]);
+    }
+
+    #[tokio::test]
+    async fn recognize_rejects_mismatched_dimensions() {
+        let (status, _) = post(
+            app().await,
+            "/api/recognize",
+            json!({ "image": [0, 0, 0], "width": 10, "height": 10 }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn recognize_rejects_too_many_digits() {
+        // A bar every 6 columns, one more than allowed.
+        let (width, height) = ((nn_engine::MAX_SEGMENTS + 1) * 6, 10);
+        let canvas: Vec<u8> = (0..width * height)
+            .map(|i| if i % width % 6 < 3 { 255 } else { 0 })
+            .collect();
+
+        let (status, _) = post(
+            app().await,
+            "/api/recognize",
+            json!({ "image": canvas, "width": width, "height": height }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn trained_samples_are_stored_with_model_version() {
+        let model = loaded_model().await;
+        let version = model.version().unwrap();
+        let (mut state, mut files) = state_with(model, LimitsConfig::default());
+        let path = files.path("samples");
+        state.samples = Arc::new(JsonLinesSampleStore::new(&path));
+        let app = router(state);
+
+        for label in [3, 11] {
+            post_with_key(
+                app.clone(),
+                "/api/train",
+                Some("trainer-key"),
+                json!({ "label": label, "image": vec![0u8; 784] }),
+            )
+            .await;
+        }
+
+        let samples = JsonLinesSampleStore::new(&path).load().await.unwrap();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].label, 3);
+        assert_eq!(samples[0].pixels.len(), 784);
+        assert_eq!(samples[0].model_version, version);
+        assert!(samples[0].timestamp > 0.0);
+    }
+
+    #[tokio::test]
+    async fn rejected_updates_answer_422_and_keep_the_model() {
+        let (state, _files) = state_with(strict_model().await, LimitsConfig::default());
+        let samples = state.samples.clone();
+        let app = router(state);
+
+        let (status, body) = post_with_key(
+            app.clone(),
+            "/api/train",
+            Some("trainer-key"),
+            json!({ "label": 1, "image": vec![0u8; 784] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains("validation accuracy")
+        );
+
+        let (_, body) = get(app, "/api/model").await;
+        assert_eq!(body["training_steps"], 0);
+        assert_eq!(body["checksum"], body["loaded_checksum"]);
+        // Rejected samples don't end up in the dataset.
+        assert!(samples.load().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn train_rejects_invalid_label() {
+        let (status, _) = post_with_key(
+            app().await,
+            "/api/train",
+            Some("admin-key"),
+            json!({ "label": 12, "image": vec![0u8; 784] }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
 }
@@ -0,0 +1,43 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use nn_engine::NNError;
+use serde_json::json;
+
+/// JSON error body `{ "error": "..." }` with a matching status code.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<NNError> for ApiError {
+    fn from(error: NNError) -> Self {
+        let status = match error {
+            NNError::InvalidInput | NNError::SerializationError => StatusCode::BAD_REQUEST,
+            NNError::IoError(_) | NNError::PersistenceError | NNError::InternalError => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            NNError::UpdateRejected { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+
+        Self::new(status, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
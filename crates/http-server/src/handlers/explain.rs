@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+};
+use nn_engine::port::async_classifier::{AsyncAttributionExplainer, AsyncDigitPredictor};
+use nn_engine::{Attribution, AttributionMethod, PreprocessOptions};
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::api::prepare_image;
+use crate::handlers::error::ApiError;
+use crate::state::AppState;
+
+/// Each integrated gradients step is a forward and backward pass.
+const MAX_STEPS: usize = 256;
+
+#[derive(Deserialize)]
+pub struct ExplainRequest {
+    pub image: Vec<u8>,
+    #[serde(default)]
+    pub width: Option<usize>,
+    #[serde(default)]
+    pub height: Option<usize>,
+    #[serde(default)]
+    pub preprocess: Option<PreprocessOptions>,
+    /// Digit to explain; the predicted one by default.
+    #[serde(default)]
+    pub class: Option<u8>,
+    #[serde(default)]
+    pub method: AttributionMethod,
+}
+
+#[derive(Serialize)]
+pub struct ExplainResponse {
+    pub digit: u8,
+    pub confidence: f32,
+    #[serde(flatten)]
+    pub attribution: Attribution,
+    /// `values` scaled into `[-1, 1]`, for drawing.
+    pub heatmap: Vec<f32>,
+    /// The 28×28 input the map refers to, after preprocessing.
+    pub pixels: Vec<u8>,
+}
+
+/// A 28×28 attribution map for the prediction, to overlay on the canvas.
+pub async fn explain(
+    State(state): State<AppState>,
+    Json(payload): Json<ExplainRequest>,
+) -> Result<Json<ExplainResponse>, ApiError> {
+    if let AttributionMethod::IntegratedGradients { steps } = payload.method
+        && steps > MAX_STEPS
+    {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            format!("At most {} integrated gradients steps", MAX_STEPS),
+        ));
+    }
+
+    let image = prepare_image(
+        payload.image,
+        payload.width,
+        payload.height,
+        payload.preprocess,
+    )
+    .await?;
+
+    // Straight to the model, like training, so explanations stay out of the
+    // prediction metrics.
+    let prediction = state.model.predict(&image).await?;
+    let class = payload.class.unwrap_or(prediction.digit);
+    let attribution = state.model.explain(&image, class, payload.method).await?;
+
+    Ok(Json(ExplainResponse {
+        digit: prediction.digit,
+        confidence: prediction.confidence,
+        heatmap: attribution.normalized(),
+        attribution,
+        pixels: image,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::*;
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn explain_returns_a_map_for_each_method() {
+        let app = app().await;
+        let mut image = vec![0u8; 784];
+        for row in 4..24 {
+            image[row * 28 + 14] = 255;
+        }
+
+        for method in [
+            json!({ "name": "saliency" }),
+            json!({ "name": "integrated_gradients", "steps": 16 }),
+            json!({ "name": "occlusion", "patch": 4, "stride": 4 }),
+        ] {
+            let (status, body) = post(
+                app.clone(),
+                "/api/explain",
+                json!({ "image": image, "method": method }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body["class"], body["digit"]);
+            assert_eq!(body["method"], method);
+            assert_eq!(body["values"].as_array().unwrap().len(), 784);
+            assert_eq!(body["pixels"], json!(image));
+            let heatmap = body["heatmap"].as_array().unwrap();
+            assert!(heatmap.iter().all(|v| v.as_f64().unwrap().abs() <= 1.0));
+        }
+
+        let (status, body) = post(
+            app.clone(),
+            "/api/explain",
+            json!({ "image": image, "class": 7 }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["class"], 7);
+        assert_eq!(body["method"]["name"], "saliency");
+
+        for invalid in [
+            json!({ "image": image, "class": 10 }),
+            json!({ "image": image, "method": { "name": "integrated_gradients", "steps": 100000 } }),
+            json!({ "image": image, "method": { "name": "occlusion", "patch": 0 } }),
+        ] {
+            let (status, _) = post(app.clone(), "/api/explain", invalid).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+        }
+    }
+}
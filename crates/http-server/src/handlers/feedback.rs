@@ -0,0 +1,293 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use nn_engine::{Feedback, FeedbackStatus, NNError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::handlers::api::{TrainRequest, prepare_image, train_and_record};
+use crate::handlers::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Serialize)]
+pub struct SubmitResponse {
+    pub id: u64,
+    pub flagged: bool,
+    pub digit: u8,
+    pub confidence: f32,
+}
+
+/// Queues a labelled sample for review instead of training on it.
+pub async fn submit(
+    State(state): State<AppState>,
+    Json(payload): Json<TrainRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if payload.label > 9 {
+        return Err(NNError::InvalidInput.into());
+    }
+
+    let image = prepare_image(
+        payload.image,
+        payload.width,
+        payload.height,
+        payload.preprocess,
+    )
+    .await?;
+    let prediction = state.classifier.predict(&image).await?;
+
+    let feedback = Feedback::new(
+        payload.label,
+        image,
+        prediction,
+        state.feedback.flag_confidence,
+    );
+    let feedback = state.feedback.queue.submit(feedback).await?;
+
+    if feedback.flagged {
+        tracing::info!(
+            "Feedback {} flagged: labelled {}, model says {} ({:.2})",
+            feedback.id,
+            feedback.label,
+            feedback.prediction.digit,
+            feedback.prediction.confidence
+        );
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SubmitResponse {
+            id: feedback.id,
+            flagged: feedback.flagged,
+            digit: feedback.prediction.digit,
+            confidence: feedback.prediction.confidence,
+        }),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct ListQuery {
+    /// All samples when absent.
+    pub status: Option<FeedbackStatus>,
+}
+
+pub async fn list(
+    State(state): State<AppState>,
+    Query(query): Query<ListQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    Ok(Json(state.feedback.queue.list(query.status).await?))
+}
+
+#[derive(Deserialize)]
+pub struct ReviewRequest {
+    pub ids: Vec<u64>,
+}
+
+#[derive(Serialize)]
+pub struct ApproveFailure {
+    pub id: u64,
+    pub error: String,
+}
+
+#[derive(Serialize)]
+pub struct ApproveResponse {
+    pub approved: usize,
+    pub mean_loss: f32,
+    /// Samples that weren't approved, with the reason; those the model
+    /// couldn't train on stay pending.
+    pub failed: Vec<ApproveFailure>,
+}
+
+/// Trains on the pending samples among `ids`, in the order given. Each one is
+/// marked approved only once its training step went through.
+pub async fn approve(
+    State(state): State<AppState>,
+    Json(payload): Json<ReviewRequest>,
+) -> Result<Json<ApproveResponse>, ApiError> {
+    // Approvals and rejections take turns, so a sample can't be trained on
+    // twice or after it was rejected.
+    let _reviewing = state.feedback.reviewing.lock().await;
+
+    let mut response = ApproveResponse {
+        approved: 0,
+        mean_loss: 0.0,
+        failed: Vec::new(),
+    };
+    for id in payload.ids {
+        let Some(feedback) = state.feedback.queue.get(id).await? else {
+            continue;
+        };
+        if feedback.status != FeedbackStatus::Pending {
+            continue;
+        }
+
+        let result = match train_and_record(&state, feedback.label, feedback.pixels).await {
+            Ok(result) => result,
+            Err(error) => {
+                tracing::warn!("Feedback {} not trained on: {}", id, error.message);
+                response.failed.push(ApproveFailure {
+                    id,
+                    error: error.message,
+                });
+                continue;
+            }
+        };
+
+        // The model has already changed, so a failed write is reported
+        // rather than aborting the rest of the batch.
+        match state
+            .feedback
+            .queue
+            .resolve(&[id], FeedbackStatus::Approved)
+            .await
+        {
+            Ok(resolved) if !resolved.is_empty() => {
+                response.approved += 1;
+                response.mean_loss += result.loss;
+            }
+            Ok(_) => response.failed.push(ApproveFailure {
+                id,
+                error: "Trained on, but no longer pending".to_string(),
+            }),
+            Err(e) => {
+                tracing::error!("Trained on feedback {} but failed to approve it: {}", id, e);
+                response.failed.push(ApproveFailure {
+                    id,
+                    error: format!("Trained on, but not marked approved: {}", e),
+                });
+            }
+        }
+    }
+    response.mean_loss /= response.approved.max(1) as f32;
+
+    tracing::info!("Trained on {} approved feedback samples", response.approved);
+
+    Ok(Json(response))
+}
+
+pub async fn reject(
+    State(state): State<AppState>,
+    Json(payload): Json<ReviewRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let _reviewing = state.feedback.reviewing.lock().await;
+    let rejected = state
+        .feedback
+        .queue
+        .resolve(&payload.ids, FeedbackStatus::Rejected)
+        .await?;
+
+    Ok(Json(json!({ "rejected": rejected.len() })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::*;
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn feedback_is_queued_until_an_admin_approves() {
+        let app = app().await;
+        let image = vec![0u8; 784];
+
+        let mut ids = Vec::new();
+        for label in [1, 2, 12] {
+            let (status, body) = post(
+                app.clone(),
+                "/api/feedback",
+                json!({ "label": label, "image": image }),
+            )
+            .await;
+            if label > 9 {
+                assert_eq!(status, StatusCode::BAD_REQUEST);
+            } else {
+                assert_eq!(status, StatusCode::ACCEPTED);
+                assert!(body["flagged"].is_boolean());
+                ids.push(body["id"].as_u64().unwrap());
+            }
+        }
+
+        // Submitting doesn't train.
+        let (_, model) = get(app.clone(), "/api/model").await;
+        assert_eq!(model["training_steps"], 0);
+
+        let (status, pending) = get_with_key(
+            app.clone(),
+            "/api/feedback?status=pending",
+            Some("admin-key"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(pending.as_array().unwrap().len(), 2);
+
+        let (status, _) = post_with_key(
+            app.clone(),
+            "/api/feedback/approve",
+            Some("trainer-key"),
+            json!({ "ids": ids }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = post_with_key(
+            app.clone(),
+            "/api/feedback/reject",
+            Some("admin-key"),
+            json!({ "ids": [ids[1]] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["rejected"], 1);
+
+        let (status, body) = post_with_key(
+            app.clone(),
+            "/api/feedback/approve",
+            Some("admin-key"),
+            json!({ "ids": ids }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["approved"], 1);
+
+        let (_, model) = get(app, "/api/model").await;
+        assert_eq!(model["training_steps"], 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_feedback_stays_pending() {
+        let app = app_with(strict_model().await);
+
+        let (_, body) = post(
+            app.clone(),
+            "/api/feedback",
+            json!({ "label": 1, "image": vec![0u8; 784] }),
+        )
+        .await;
+        let id = body["id"].as_u64().unwrap();
+
+        let (status, body) = post_with_key(
+            app.clone(),
+            "/api/feedback/approve",
+            Some("admin-key"),
+            json!({ "ids": [id] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["approved"], 0);
+        assert_eq!(body["failed"][0]["id"], id);
+
+        let (_, pending) =
+            get_with_key(app, "/api/feedback?status=pending", Some("admin-key")).await;
+        assert_eq!(pending[0]["id"], id);
+    }
+
+    #[tokio::test]
+    async fn feedback_list_requires_admin() {
+        assert_eq!(
+            get(app().await, "/api/feedback").await.0,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
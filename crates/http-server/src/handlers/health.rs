@@ -0,0 +1,74 @@
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+use crate::handlers::error::ApiError;
+use crate::state::AppState;
+
+pub(crate) fn model_not_loaded() -> ApiError {
+    ApiError::new(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Model is not loaded; check MNIST_MODEL_PATH and POST /api/model/reload",
+    )
+}
+
+/// Liveness: the process is up, even without a model.
+pub async fn healthz() -> impl IntoResponse {
+    Json(json!({ "status": "ok" }))
+}
+
+/// Readiness: a model has been loaded and requests can be served.
+pub async fn readyz(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    if !state.model.is_loaded() {
+        return Err(model_not_loaded());
+    }
+
+    Ok(Json(json!({ "status": "ready" })))
+}
+
+/// Rejects classifier routes with 503 while the server runs degraded.
+pub async fn require_model(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if !state.model.is_loaded() {
+        return Err(model_not_loaded());
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::*;
+    use axum::http::StatusCode;
+    use nn_engine::DigitClassifierService;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn missing_model_starts_degraded() {
+        let app = app_with(Arc::new(DigitClassifierService::from_path(
+            "/nonexistent/model.bin",
+        )));
+
+        assert_eq!(get(app.clone(), "/healthz").await.0, StatusCode::OK);
+
+        let (status, body) = get(app.clone(), "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body["error"].as_str().unwrap().contains("not loaded"));
+
+        assert_eq!(
+            get(app.clone(), "/api/model").await.0,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        let (status, _) = post(app, "/api/predict", json!({ "image": vec![0u8; 784] })).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -1,2 +1,9 @@
 pub mod api;
+pub mod error;
+pub mod explain;
+pub mod feedback;
+pub mod health;
+pub mod metrics;
+pub mod model;
 pub mod page;
+pub mod visualize;
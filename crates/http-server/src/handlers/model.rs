@@ -0,0 +1,64 @@
+use axum::{Json, extract::State, response::IntoResponse};
+use serde_json::json;
+
+use crate::handlers::error::ApiError;
+use crate::handlers::health::model_not_loaded;
+use crate::state::AppState;
+
+pub async fn model(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    match state.model.status().await? {
+        Some(status) => Ok(Json(status)),
+        None => Err(model_not_loaded()),
+    }
+}
+
+/// Writes the current weights, including online training, back to the model file.
+pub async fn save(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    state.model.save_model().await?;
+    tracing::info!("Model saved");
+
+    Ok(Json(json!({ "status": "saved" })))
+}
+
+/// Re-reads the model file, discarding online training since the last save.
+pub async fn reload(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    state.model.load_model().await?;
+
+    let status = state.model.status().await?.ok_or_else(model_not_loaded)?;
+    state
+        .metrics
+        .set_model_checksum(status.loaded_checksum.clone());
+    tracing::info!("Model reloaded: {}", status.loaded_checksum);
+
+    Ok(Json(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::*;
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn model_reports_training_since_load() {
+        let app = app().await;
+        assert_eq!(get(app.clone(), "/readyz").await.0, StatusCode::OK);
+
+        let (status, _) = post_with_key(
+            app.clone(),
+            "/api/train",
+            Some("trainer-key"),
+            json!({ "label": 1, "image": vec![0u8; 784] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = get(app, "/api/model").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["parameters"], 101_770);
+        assert_eq!(body["training_steps"], 1);
+        assert_ne!(body["checksum"], body["loaded_checksum"]);
+        assert!(body["architecture"].is_string());
+        assert!(body["loaded_at"].as_f64().unwrap() > 0.0);
+    }
+}
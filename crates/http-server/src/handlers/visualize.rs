@@ -0,0 +1,172 @@
+use axum::{
+    extract::{Json, Query, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use nn_engine::port::async_classifier::{AsyncActivationExtractor, AsyncModelStateExporter};
+use nn_engine::{
+    Activations, NNError, WeightGridOptions, encode_png, upscale, weight_grid, weight_tiles,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::api::{PredictRequest, prepare_image};
+use crate::handlers::error::ApiError;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct WeightsQuery {
+    pub columns: Option<usize>,
+    pub padding: Option<usize>,
+    pub scale: Option<usize>,
+    #[serde(default)]
+    pub shared_scale: bool,
+    /// Renders only this hidden unit's tile instead of the grid.
+    pub unit: Option<usize>,
+}
+
+/// First-layer weights as a PNG: every hidden unit's `w1` row as a 28×28
+/// tile in a grid, or a single unit's tile.
+pub async fn weights(
+    State(state): State<AppState>,
+    Query(query): Query<WeightsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let defaults = WeightGridOptions::default();
+    let options = WeightGridOptions {
+        columns: query.columns.unwrap_or(defaults.columns),
+        padding: query.padding.unwrap_or(defaults.padding),
+        scale: query.scale.unwrap_or(defaults.scale),
+        shared_scale: query.shared_scale,
+    };
+    let model = state.model.export_state().await?;
+
+    let png = tokio::task::spawn_blocking(move || {
+        let image = match query.unit {
+            Some(unit) => {
+                options.validate()?;
+                let tiles = weight_tiles(&model, options.shared_scale)?;
+                upscale(tiles.get(unit).ok_or(NNError::InvalidInput)?, options.scale)
+            }
+            None => weight_grid(&model, &options)?,
+        };
+        encode_png(&image)
+    })
+    .await
+    .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))??;
+
+    Ok(([(header::CONTENT_TYPE, "image/png")], png))
+}
+
+#[derive(Serialize)]
+pub struct ActivationsResponse {
+    pub digit: u8,
+    pub confidence: f32,
+    #[serde(flatten)]
+    pub activations: Activations,
+}
+
+/// What each layer computes for an image, from the 128 hidden units to the
+/// output probabilities.
+pub async fn activations(
+    State(state): State<AppState>,
+    Json(payload): Json<PredictRequest>,
+) -> Result<Json<ActivationsResponse>, ApiError> {
+    let image = prepare_image(
+        payload.image,
+        payload.width,
+        payload.height,
+        payload.preprocess,
+    )
+    .await?;
+    let activations = state.model.activations(&image).await?;
+
+    let (digit, confidence) = activations
+        .probabilities
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(digit, &p)| (digit as u8, p))
+        .ok_or(NNError::InternalError)?;
+
+    Ok(Json(ActivationsResponse {
+        digit,
+        confidence,
+        activations,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::*;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn activations_report_every_layer() {
+        let mut image = vec![0u8; 784];
+        for row in 4..24 {
+            image[row * 28 + 14] = 255;
+        }
+
+        let (status, body) = post(app().await, "/api/activations", json!({ "image": image })).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["hidden"].as_array().unwrap().len(), 128);
+        assert_eq!(body["logits"].as_array().unwrap().len(), 10);
+        let probabilities = body["probabilities"].as_array().unwrap();
+        let digit = body["digit"].as_u64().unwrap() as usize;
+        assert_eq!(probabilities[digit], body["confidence"]);
+    }
+
+    async fn get_png(app: Router, uri: &str) -> (StatusCode, Vec<u8>) {
+        let response = app
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        if status == StatusCode::OK {
+            assert_eq!(response.headers()["content-type"], "image/png");
+        }
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+
+        (status, bytes.to_vec())
+    }
+
+    #[tokio::test]
+    async fn weights_render_as_png_grid_or_tile() {
+        let app = app().await;
+        // Width and height live at bytes 16..24 of the IHDR chunk.
+        let size = |png: &[u8]| {
+            assert!(png.starts_with(b"\x89PNG"));
+            let be = |at: usize| u32::from_be_bytes(png[at..at + 4].try_into().unwrap());
+            (be(16), be(20))
+        };
+
+        let (status, png) = get_png(app.clone(), "/api/model/weights.png").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(size(&png), (16 * 29 + 1, 8 * 29 + 1));
+
+        let (status, png) = get_png(
+            app.clone(),
+            "/api/model/weights.png?columns=8&padding=0&scale=2&shared_scale=true",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(size(&png), (8 * 28 * 2, 16 * 28 * 2));
+
+        let (status, png) = get_png(app.clone(), "/api/model/weights.png?unit=127&scale=4").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(size(&png), (112, 112));
+
+        for invalid in ["unit=128", "scale=100", "columns=0"] {
+            let (status, _) =
+                get_png(app.clone(), &format!("/api/model/weights.png?{invalid}")).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! Request limits: body size, timeouts, concurrency and per-client rates.
+//!
+//! Body size, timeout and concurrency are plain tower layers applied to the
+//! whole router; the rate limiter is a token bucket per API key (for known
+//! keys) or per client IP, applied to the `/api` routes.
+
+use axum::{
+    BoxError,
+    extract::{ConnectInfo, Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::auth::presented_key;
+use crate::handlers::error::ApiError;
+use crate::state::AppState;
+
+/// Buckets kept; beyond this the least recently used ones are dropped.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimit {
+    /// Sustained requests per second per client.
+    pub per_second: f64,
+    /// Requests a client may send at once after being idle.
+    pub burst: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct LimitsConfig {
+    /// Larger request bodies are rejected with 413.
+    pub max_body_bytes: usize,
+    /// Requests taking longer are answered with 408.
+    pub request_timeout: Duration,
+    /// Requests in flight beyond this are shed with 503.
+    pub max_concurrency: usize,
+    /// `None` disables rate limiting.
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 4 * 1024 * 1024,
+            request_timeout: Duration::from_secs(30),
+            max_concurrency: 512,
+            rate_limit: Some(RateLimit {
+                per_second: 20.0,
+                burst: 40.0,
+            }),
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// Key of this client in [`Clients::recent`].
+    last_use: u64,
+}
+
+/// Token buckets with least-recently-used eviction.
+#[derive(Default)]
+struct Clients {
+    buckets: HashMap<String, Bucket>,
+    /// Clients by last use, oldest first.
+    recent: BTreeMap<u64, String>,
+    next_use: u64,
+}
+
+pub struct Limits {
+    pub config: LimitsConfig,
+    clients: Mutex<Clients>,
+}
+
+impl Limits {
+    pub fn new(config: LimitsConfig) -> Self {
+        Self {
+            config,
+            clients: Mutex::default(),
+        }
+    }
+
+    /// Takes a token from `client`'s bucket, or returns how long until one
+    /// is available.
+    fn acquire(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let Some(rate) = self.config.rate_limit else {
+            return Ok(());
+        };
+        let mut clients = self.clients.lock().unwrap();
+        let clients = &mut *clients;
+
+        if !clients.buckets.contains_key(client) {
+            while clients.buckets.len() >= MAX_TRACKED_CLIENTS {
+                let Some((_, oldest)) = clients.recent.pop_first() else {
+                    break;
+                };
+                clients.buckets.remove(&oldest);
+            }
+        }
+
+        let last_use = clients.next_use;
+        clients.next_use += 1;
+        let bucket = clients.buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: rate.burst,
+            updated: now,
+            last_use,
+        });
+        clients.recent.remove(&bucket.last_use);
+        clients.recent.insert(last_use, client.to_string());
+        bucket.last_use = last_use;
+
+        let idle = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + idle * rate.per_second).min(rate.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / rate.per_second,
+            ))
+        }
+    }
+}
+
+/// Rate limits per known API key, falling back to the client's IP.
+pub async fn rate_limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let key = presented_key(request.headers()).filter(|key| state.auth.role(key).is_some());
+    let client = match key {
+        Some(key) => format!("key:{key}"),
+        None => match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+            Some(ConnectInfo(addr)) => format!("ip:{}", addr.ip()),
+            None => "ip:unknown".to_string(),
+        },
+    };
+
+    match state.limits.acquire(&client, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => (
+            [(
+                header::RETRY_AFTER,
+                retry_after.as_secs_f64().ceil().max(1.0).to_string(),
+            )],
+            ApiError::new(StatusCode::TOO_MANY_REQUESTS, "Too many requests"),
+        )
+            .into_response(),
+    }
+}
+
+/// Maps errors from the timeout and load-shedding layers to responses.
+pub async fn handle_error(error: BoxError) -> ApiError {
+    if error.is::<tower::timeout::error::Elapsed>() {
+        ApiError::new(StatusCode::REQUEST_TIMEOUT, "Request timed out")
+    } else if error.is::<tower::load_shed::error::Overloaded>() {
+        ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "Server is overloaded")
+    } else {
+        ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+    use axum::body::Body;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    fn limits(per_second: f64, burst: f64) -> Limits {
+        Limits::new(LimitsConfig {
+            rate_limit: Some(RateLimit { per_second, burst }),
+            ..LimitsConfig::default()
+        })
+    }
+
+    #[test]
+    fn bucket_refills_over_time() {
+        let limits = limits(2.0, 2.0);
+        let start = Instant::now();
+
+        assert!(limits.acquire("a", start).is_ok());
+        assert!(limits.acquire("a", start).is_ok());
+        let wait = limits.acquire("a", start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+
+        // Other clients have their own bucket.
+        assert!(limits.acquire("b", start).is_ok());
+
+        assert!(
+            limits
+                .acquire("a", start + Duration::from_millis(500))
+                .is_ok()
+        );
+        assert!(
+            limits
+                .acquire("a", start + Duration::from_millis(500))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn tracked_clients_are_capped() {
+        let limits = limits(1.0, 2.0);
+        let now = Instant::now();
+
+        // Every client keeps a partly drained bucket.
+        for client in 0..=MAX_TRACKED_CLIENTS {
+            limits.acquire(&client.to_string(), now).unwrap();
+        }
+
+        let clients = limits.clients.lock().unwrap();
+        assert_eq!(clients.buckets.len(), MAX_TRACKED_CLIENTS);
+        assert_eq!(clients.recent.len(), MAX_TRACKED_CLIENTS);
+        // The least recently used client went first.
+        assert!(!clients.buckets.contains_key("0"));
+        assert!(clients.buckets.contains_key("1"));
+    }
+
+    #[test]
+    fn disabled_rate_limit_always_admits() {
+        let limits = Limits::new(LimitsConfig {
+            rate_limit: None,
+            ..LimitsConfig::default()
+        });
+
+        assert!((0..1000).all(|_| limits.acquire("a", Instant::now()).is_ok()));
+    }
+
+    #[tokio::test]
+    async fn timeouts_and_overload_have_distinct_statuses() {
+        let elapsed: BoxError = Box::new(tower::timeout::error::Elapsed::new());
+        let overloaded: BoxError = Box::new(tower::load_shed::error::Overloaded::new());
+
+        assert_eq!(
+            handle_error(elapsed).await.status,
+            StatusCode::REQUEST_TIMEOUT
+        );
+        assert_eq!(
+            handle_error(overloaded).await.status,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    fn predict_from(ip: [u8; 4]) -> Request<Body> {
+        let mut request = Request::post("/api/predict")
+            .header("content-type", "application/json")
+            .body(Body::from(json!({ "image": vec![0u8; 784] }).to_string()))
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(std::net::SocketAddr::from((ip, 4000))));
+        request
+    }
+
+    #[tokio::test]
+    async fn clients_over_their_rate_get_429() {
+        let app = app_with_limits(
+            loaded_model().await,
+            LimitsConfig {
+                rate_limit: Some(RateLimit {
+                    per_second: 0.001,
+                    burst: 2.0,
+                }),
+                ..LimitsConfig::default()
+            },
+        );
+
+        for _ in 0..2 {
+            let response = app.clone().oneshot(predict_from([10, 0, 0, 1])).await;
+            assert_eq!(response.unwrap().status(), StatusCode::OK);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(predict_from([10, 0, 0, 1]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("retry-after"));
+
+        // Other clients and the probes are unaffected.
+        let response = app.clone().oneshot(predict_from([10, 0, 0, 2])).await;
+        assert_eq!(response.unwrap().status(), StatusCode::OK);
+        assert_eq!(get(app, "/healthz").await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn oversized_bodies_get_413() {
+        let app = app_with_limits(
+            loaded_model().await,
+            LimitsConfig {
+                max_body_bytes: 1024,
+                ..LimitsConfig::default()
+            },
+        );
+
+        let response = app
+            .clone()
+            .oneshot(predict_from([10, 0, 0, 1]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let upload = Request::post("/api/predict/image")
+            .header("content-type", "image/png")
+            .body(Body::from(vec![0u8; 2048]))
+            .unwrap();
+        let response = app.oneshot(upload).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -1,9 +1,16 @@
+mod auth;
 mod classifier;
+mod config;
 mod handlers;
+mod limits;
+mod metrics;
 mod routes;
 mod server;
 mod state;
 mod templates;
+#[cfg(test)]
+mod test_support;
+mod upload;
 
 #[tokio::main]
 async fn main() {
@@ -0,0 +1,505 @@
+//! Prometheus metrics, rendered in the text exposition format at `/metrics`.
+
+use async_trait::async_trait;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::classifier::BatchMetricsSnapshot;
+use crate::state::AppState;
+use nn_engine::port::async_classifier::{AsyncDigitPredictor, AsyncDigitTrainer};
+use nn_engine::{LockWaitMetrics, NNError, Prediction, TrainingStepResult};
+
+/// Upper bounds (seconds) of the request latency buckets.
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0,
+];
+/// Upper bounds of the prediction confidence buckets.
+const CONFIDENCE_BUCKETS: [f64; 9] = [0.2, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 0.95, 0.99];
+
+struct Histogram {
+    bounds: &'static [f64],
+    /// Non-cumulative; the last bucket has no upper bound.
+    counts: Vec<u64>,
+    sum: f64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[bucket] += 1;
+        self.sum += value;
+    }
+
+    fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Writes the `_bucket`, `_sum` and `_count` series; `labels` is either
+    /// empty or a comma-terminated label list.
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        let bounds = self.bounds.iter().map(|b| b.to_string());
+
+        for (le, count) in bounds.chain(["+Inf".to_string()]).zip(&self.counts) {
+            cumulative += count;
+            let _ = writeln!(out, "{name}_bucket{{{labels}le=\"{le}\"}} {cumulative}");
+        }
+
+        let labels = match labels.trim_end_matches(',') {
+            "" => String::new(),
+            labels => format!("{{{labels}}}"),
+        };
+        let _ = writeln!(out, "{name}_sum{labels} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{labels} {}", self.count());
+    }
+}
+
+#[derive(Default)]
+struct TrainingStats {
+    loss_sum: f64,
+    last_loss: f64,
+}
+
+pub struct Metrics {
+    /// Request counts keyed by `(route, method, status)`.
+    requests: Mutex<BTreeMap<(String, String, u16), u64>>,
+    /// Latency histograms keyed by `(route, method)`.
+    latency: Mutex<BTreeMap<(String, String), Histogram>>,
+    predictions: [AtomicU64; 10],
+    confidence: Mutex<Histogram>,
+    training_steps: AtomicU64,
+    training: Mutex<TrainingStats>,
+    model_checksum: RwLock<Option<String>>,
+    /// Steps since [`set_model_checksum`](Self::set_model_checksum), the
+    /// `+N` of the served model's version.
+    model_training_steps: AtomicU64,
+    engine_lock: Option<Arc<LockWaitMetrics>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            requests: Mutex::default(),
+            latency: Mutex::default(),
+            predictions: Default::default(),
+            confidence: Mutex::new(Histogram::new(&CONFIDENCE_BUCKETS)),
+            training_steps: AtomicU64::new(0),
+            training: Mutex::default(),
+            model_checksum: RwLock::new(None),
+            model_training_steps: AtomicU64::new(0),
+            engine_lock: None,
+        }
+    }
+}
+
+impl Metrics {
+    /// Also reports how long training waits for the engine's lock.
+    pub fn with_engine_lock(lock_wait: Arc<LockWaitMetrics>) -> Self {
+        Self {
+            engine_lock: Some(lock_wait),
+            ..Self::default()
+        }
+    }
+
+    /// Called whenever a model is loaded; restarts the model's step count.
+    pub fn set_model_checksum(&self, checksum: String) {
+        *self.model_checksum.write().unwrap() = Some(checksum);
+        self.model_training_steps.store(0, Ordering::Relaxed);
+    }
+
+    pub fn record_request(&self, route: &str, method: &str, status: u16, elapsed: Duration) {
+        let key = (route.to_string(), method.to_string());
+
+        *self
+            .requests
+            .lock()
+            .unwrap()
+            .entry((key.0.clone(), key.1.clone(), status))
+            .or_default() += 1;
+
+        self.latency
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Histogram::new(&LATENCY_BUCKETS))
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn record_prediction(&self, prediction: &Prediction) {
+        if let Some(count) = self.predictions.get(prediction.digit as usize) {
+            count.fetch_add(1, Ordering::Relaxed);
+        }
+        self.confidence
+            .lock()
+            .unwrap()
+            .observe(prediction.confidence as f64);
+    }
+
+    fn record_training(&self, step: &TrainingStepResult) {
+        self.training_steps.fetch_add(1, Ordering::Relaxed);
+        self.model_training_steps.fetch_add(1, Ordering::Relaxed);
+
+        let mut training = self.training.lock().unwrap();
+        training.loss_sum += step.loss as f64;
+        training.last_loss = step.loss as f64;
+    }
+
+    pub fn render(&self, batching: &BatchMetricsSnapshot) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "mnist_http_requests_total",
+            "counter",
+            "HTTP requests by route, method and status.",
+        );
+        for ((route, method, status), count) in self.requests.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "mnist_http_requests_total{{route=\"{route}\",method=\"{method}\",status=\"{status}\"}} {count}"
+            );
+        }
+
+        header(
+            &mut out,
+            "mnist_http_request_duration_seconds",
+            "histogram",
+            "HTTP request latency by route and method.",
+        );
+        for ((route, method), histogram) in self.latency.lock().unwrap().iter() {
+            histogram.render(
+                &mut out,
+                "mnist_http_request_duration_seconds",
+                &format!("route=\"{route}\",method=\"{method}\","),
+            );
+        }
+
+        header(
+            &mut out,
+            "mnist_predictions_total",
+            "counter",
+            "Predictions by predicted digit.",
+        );
+        for (digit, count) in self.predictions.iter().enumerate() {
+            let count = count.load(Ordering::Relaxed);
+            let _ = writeln!(out, "mnist_predictions_total{{digit=\"{digit}\"}} {count}");
+        }
+
+        let confidence = self.confidence.lock().unwrap();
+        header(
+            &mut out,
+            "mnist_prediction_confidence",
+            "histogram",
+            "Confidence of the predicted digit.",
+        );
+        confidence.render(&mut out, "mnist_prediction_confidence", "");
+        gauge(
+            &mut out,
+            "mnist_prediction_confidence_mean",
+            "Mean confidence over all predictions.",
+            confidence.sum / confidence.count().max(1) as f64,
+        );
+        drop(confidence);
+
+        let training = self.training.lock().unwrap();
+        header(
+            &mut out,
+            "mnist_training_steps_total",
+            "counter",
+            "Online training steps since startup.",
+        );
+        let _ = writeln!(
+            out,
+            "mnist_training_steps_total {}",
+            self.training_steps.load(Ordering::Relaxed)
+        );
+        header(
+            &mut out,
+            "mnist_training_loss_sum",
+            "counter",
+            "Sum of online training losses; divide by steps for the mean.",
+        );
+        let _ = writeln!(out, "mnist_training_loss_sum {}", training.loss_sum);
+        gauge(
+            &mut out,
+            "mnist_training_last_loss",
+            "Loss of the most recent training step.",
+            training.last_loss,
+        );
+        drop(training);
+
+        if let Some(checksum) = self.model_checksum.read().unwrap().as_ref() {
+            header(
+                &mut out,
+                "mnist_model_info",
+                "gauge",
+                "SHA-256 of the model as loaded at startup or reload.",
+            );
+            let _ = writeln!(out, "mnist_model_info{{checksum=\"{checksum}\"}} 1");
+            gauge(
+                &mut out,
+                "mnist_model_training_steps",
+                "Online training steps applied to the served model since it was loaded.",
+                self.model_training_steps.load(Ordering::Relaxed) as f64,
+            );
+        }
+
+        if let Some(lock) = &self.engine_lock {
+            let wait = lock.snapshot();
+            header(
+                &mut out,
+                "mnist_engine_lock_acquisitions_total",
+                "counter",
+                "Model updates that took the engine's training lock.",
+            );
+            let _ = writeln!(
+                out,
+                "mnist_engine_lock_acquisitions_total {}",
+                wait.acquisitions
+            );
+            header(
+                &mut out,
+                "mnist_engine_lock_wait_seconds_total",
+                "counter",
+                "Time model updates spent waiting for the training lock.",
+            );
+            let _ = writeln!(
+                out,
+                "mnist_engine_lock_wait_seconds_total {}",
+                wait.total.as_secs_f64()
+            );
+            gauge(
+                &mut out,
+                "mnist_engine_lock_wait_max_seconds",
+                "Longest single wait for the training lock.",
+                wait.max.as_secs_f64(),
+            );
+        }
+
+        header(
+            &mut out,
+            "mnist_batches_total",
+            "counter",
+            "Prediction batches dispatched.",
+        );
+        let _ = writeln!(out, "mnist_batches_total {}", batching.batches);
+        header(
+            &mut out,
+            "mnist_batch_size",
+            "histogram",
+            "Requests merged into one prediction batch.",
+        );
+        let mut cumulative = 0;
+        for (bound, count) in &batching.batch_size_histogram {
+            cumulative += count;
+            let le = bound.map_or("+Inf".to_string(), |b| b.to_string());
+            let _ = writeln!(out, "mnist_batch_size_bucket{{le=\"{le}\"}} {cumulative}");
+        }
+        let _ = writeln!(
+            out,
+            "mnist_batch_size_sum {}\nmnist_batch_size_count {}",
+            batching.requests, batching.batches
+        );
+        gauge(
+            &mut out,
+            "mnist_batch_queue_wait_mean_seconds",
+            "Mean time a prediction waited in the batching queue.",
+            batching.mean_queue_latency_ms / 1000.0,
+        );
+        gauge(
+            &mut out,
+            "mnist_batch_queue_wait_max_seconds",
+            "Longest time a prediction waited in the batching queue.",
+            batching.max_queue_latency_ms / 1000.0,
+        );
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}");
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: f64) {
+    header(out, name, "gauge", help);
+    let _ = writeln!(out, "{name} {value}");
+}
+
+/// Counts requests and their latency per matched route.
+pub async fn track_requests(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", |path| path.as_str())
+        .to_string();
+    let method = request.method().clone();
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+
+    state.metrics.record_request(
+        &route,
+        method.as_str(),
+        response.status().as_u16(),
+        started.elapsed(),
+    );
+
+    response
+}
+
+/// Records every prediction and training step passing through `inner`.
+pub struct InstrumentedClassifier<C> {
+    inner: Arc<C>,
+    metrics: Arc<Metrics>,
+}
+
+impl<C> InstrumentedClassifier<C> {
+    pub fn new(inner: Arc<C>, metrics: Arc<Metrics>) -> Self {
+        Self { inner, metrics }
+    }
+}
+
+#[async_trait]
+impl<C> AsyncDigitPredictor for InstrumentedClassifier<C>
+where
+    C: AsyncDigitPredictor + Send + Sync,
+{
+    async fn predict(&self, pixels: &[u8]) -> Result<Prediction, NNError> {
+        let prediction = self.inner.predict(pixels).await?;
+        self.metrics.record_prediction(&prediction);
+        Ok(prediction)
+    }
+}
+
+#[async_trait]
+impl<C> AsyncDigitTrainer for InstrumentedClassifier<C>
+where
+    C: AsyncDigitTrainer + Send + Sync,
+{
+    async fn train(&self, label: u8, pixels: &[u8]) -> Result<TrainingStepResult, NNError> {
+        let step = self.inner.train(label, pixels).await?;
+        self.metrics.record_training(&step);
+        Ok(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classifier::BatchMetrics;
+    use crate::test_support::*;
+    use axum::{body::Body, http::StatusCode};
+    use http_body_util::BodyExt;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let mut histogram = Histogram::new(&[1.0, 2.0]);
+        for value in [0.5, 1.5, 1.7, 9.0] {
+            histogram.observe(value);
+        }
+
+        let mut out = String::new();
+        histogram.render(&mut out, "h", "route=\"/x\",");
+
+        assert_eq!(
+            out,
+            "h_bucket{route=\"/x\",le=\"1\"} 1\n\
+             h_bucket{route=\"/x\",le=\"2\"} 3\n\
+             h_bucket{route=\"/x\",le=\"+Inf\"} 4\n\
+             h_sum{route=\"/x\"} 12.7\n\
+             h_count{route=\"/x\"} 4\n"
+        );
+    }
+
+    #[test]
+    fn renders_predictions_training_and_model_version() {
+        let metrics = Metrics::with_engine_lock(Arc::new(LockWaitMetrics::default()));
+        metrics.set_model_checksum("abc".to_string());
+        metrics.record_prediction(&Prediction {
+            digit: 3,
+            confidence: 0.5,
+        });
+        metrics.record_prediction(&Prediction {
+            digit: 3,
+            confidence: 1.0,
+        });
+        metrics.record_training(&TrainingStepResult {
+            loss: 0.25,
+            correct: true,
+        });
+
+        let out = metrics.render(&BatchMetrics::default().snapshot());
+
+        assert!(out.contains("mnist_predictions_total{digit=\"3\"} 2\n"));
+        assert!(out.contains("mnist_prediction_confidence_mean 0.75\n"));
+        assert!(out.contains("mnist_training_steps_total 1\n"));
+        assert!(out.contains("mnist_training_last_loss 0.25\n"));
+        assert!(out.contains("mnist_model_info{checksum=\"abc\"} 1\n"));
+        assert!(out.contains("mnist_model_training_steps 1\n"));
+
+        // Reloading restarts the model's steps, not the process total.
+        metrics.set_model_checksum("def".to_string());
+        let reloaded = metrics.render(&BatchMetrics::default().snapshot());
+        assert!(reloaded.contains("mnist_model_training_steps 0\n"));
+        assert!(reloaded.contains("mnist_training_steps_total 1\n"));
+        assert!(out.contains("mnist_engine_lock_acquisitions_total 0\n"));
+        assert!(out.contains("mnist_batch_size_bucket{le=\"+Inf\"} 0\n"));
+    }
+
+    #[tokio::test]
+    async fn metrics_report_requests_and_predictions() {
+        let app = app().await;
+        let (status, _) = post(
+            app.clone(),
+            "/api/predict",
+            json!({ "image": vec![0u8; 784] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        post(app.clone(), "/api/predict", json!({ "image": [1] })).await;
+
+        let response = app
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+
+        for line in [
+            "mnist_http_requests_total{route=\"/api/predict\",method=\"POST\",status=\"200\"} 1",
+            "mnist_http_requests_total{route=\"/api/predict\",method=\"POST\",status=\"400\"} 1",
+            "mnist_http_request_duration_seconds_count{route=\"/api/predict\",method=\"POST\"} 2",
+            "mnist_prediction_confidence_count 1",
+        ] {
+            assert!(text.contains(line), "missing {line} in\n{text}");
+        }
+    }
+}
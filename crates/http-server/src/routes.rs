@@ -1,13 +1,83 @@
+use crate::auth::{require_admin, require_trainer};
 use crate::handlers;
+use crate::limits::{handle_error, rate_limit};
+use crate::metrics::track_requests;
 use crate::state::AppState;
 use axum::{
     Router,
+    error_handling::HandleErrorLayer,
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{get, post},
 };
+use tower::ServiceBuilder;
+
+pub fn router(state: AppState) -> Router {
+    let trainer = middleware::from_fn_with_state(state.clone(), require_trainer);
+    let admin = middleware::from_fn_with_state(state.clone(), require_admin);
+    let limits = state.limits.config.clone();
+
+    // Routes that need a loaded model answer 503 while the server is degraded.
+    let classifier = Router::new()
+        .route("/api/predict", post(handlers::api::predict))
+        .route("/api/predict/image", post(handlers::api::predict_image))
+        .route("/api/recognize", post(handlers::api::recognize))
+        .route("/api/explain", post(handlers::explain::explain))
+        .route("/api/activations", post(handlers::visualize::activations))
+        .route("/api/model/weights.png", get(handlers::visualize::weights))
+        .route(
+            "/api/train",
+            post(handlers::api::train).route_layer(trainer),
+        )
+        .route(
+            "/api/feedback",
+            post(handlers::feedback::submit)
+                .merge(get(handlers::feedback::list).route_layer(admin.clone())),
+        )
+        .route(
+            "/api/feedback/approve",
+            post(handlers::feedback::approve).route_layer(admin.clone()),
+        )
+        .route(
+            "/api/feedback/reject",
+            post(handlers::feedback::reject).route_layer(admin.clone()),
+        )
+        .route(
+            "/api/model/save",
+            post(handlers::model::save).route_layer(admin.clone()),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            handlers::health::require_model,
+        ));
+
+    let api = Router::new()
+        .route("/api/model", get(handlers::model::model))
+        .route(
+            "/api/model/reload",
+            post(handlers::model::reload).route_layer(admin),
+        )
+        .route("/api/stats/batching", get(handlers::api::batching_stats))
+        .merge(classifier)
+        .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit));
 
-pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", get(handlers::page::index))
-        .route("/api/predict", post(handlers::api::predict))
-        .route("/api/train", post(handlers::api::train))
+        .route("/healthz", get(handlers::health::healthz))
+        .route("/readyz", get(handlers::health::readyz))
+        .route("/metrics", get(handlers::metrics::metrics))
+        .merge(api)
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            track_requests,
+        ))
+        .layer(DefaultBodyLimit::max(limits.max_body_bytes))
+        .layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(handle_error))
+                .load_shed()
+                .concurrency_limit(limits.max_concurrency)
+                .timeout(limits.request_timeout),
+        )
+        .with_state(state)
 }
@@ -1,7 +1,15 @@
+use crate::classifier::BatchingClassifier;
+use crate::config::{ReplayConfig, ServerConfig};
+use crate::limits::Limits;
+use crate::metrics::{InstrumentedClassifier, Metrics};
 use crate::routes::router;
 
-use crate::state::AppState;
-use nn_engine::DigitClassifierService;
+use crate::state::{AppState, FeedbackState};
+use nn_engine::{
+    DigitClassifierService, JsonLinesFeedbackQueue, JsonLinesSampleStore, NNError, ReplayBuffer,
+    read_training_csv,
+};
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -14,23 +22,95 @@ pub async fn run() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let model_path = format!(
-        "{}/../../assets/models/default.bin",
-        env!("CARGO_MANIFEST_DIR")
-    );
-    let service = DigitClassifierService::from_path(model_path);
-    service.load_model().await.expect("Can't load model");
+    let config = ServerConfig::from_env();
+    if config.api_keys.is_empty() {
+        tracing::warn!("MNIST_API_KEYS is not set: training and model management are disabled");
+    }
+
+    let mut service = DigitClassifierService::from_path(config.model_path.clone());
+    if let Some(replay) = &config.replay {
+        match load_replay(replay) {
+            Ok(buffer) => service = service.with_replay(buffer),
+            Err(e) => tracing::error!(
+                "Can't read replay samples from {}: {}. Training without rehearsal",
+                replay.path,
+                e
+            ),
+        }
+    }
+    let service = Arc::new(service);
+    let metrics = Arc::new(Metrics::with_engine_lock(service.lock_wait()));
+
+    match service.load_model().await {
+        Ok(()) => {
+            if let Ok(Some(status)) = service.status().await {
+                metrics.set_model_checksum(status.loaded_checksum);
+            }
+        }
+        Err(e) => tracing::error!(
+            "Can't load model from {}: {}. Starting degraded: /readyz and the classifier routes answer 503",
+            config.model_path,
+            e
+        ),
+    }
+
+    for path in [&config.feedback_path, &config.samples_path] {
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+    }
+    let feedback = JsonLinesFeedbackQueue::open(&config.feedback_path)
+        .unwrap_or_else(|e| panic!("Can't open feedback queue {}: {}", config.feedback_path, e));
+
+    let classifier = BatchingClassifier::new(service.clone(), config.batching);
 
     let state = AppState {
-        classifier: Arc::new(service),
+        batching: classifier.metrics(),
+        classifier: Arc::new(InstrumentedClassifier::new(
+            Arc::new(classifier),
+            metrics.clone(),
+        )),
+        metrics,
+        model: service,
+        auth: Arc::new(config.api_keys),
+        limits: Arc::new(Limits::new(config.limits)),
+        feedback: Arc::new(FeedbackState {
+            queue: Arc::new(feedback),
+            flag_confidence: config.feedback_flag_confidence,
+            reviewing: Default::default(),
+        }),
+        samples: Arc::new(JsonLinesSampleStore::new(&config.samples_path)),
     };
 
     // build our application with some routes
-    let app = router().with_state(state);
+    let app = router(state);
     // run it
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
-        .await
-        .unwrap();
+    let listener = tokio::net::TcpListener::bind(&config.addr).await.unwrap();
     tracing::debug!("listening on {}", listener.local_addr().unwrap());
-    let _ = axum::serve(listener, app).await;
+    let _ = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await;
+}
+
+fn load_replay(config: &ReplayConfig) -> Result<ReplayBuffer, NNError> {
+    let file = std::fs::File::open(&config.path).map_err(|e| NNError::IoError(e.to_string()))?;
+    let mut rehearsal = read_training_csv(
+        std::io::BufReader::new(file),
+        config.samples + config.validation_samples,
+    )?;
+    let validation = rehearsal.split_off(config.samples.min(rehearsal.len()));
+    tracing::info!(
+        "Replaying {} samples per update from {} rehearsal samples; {} held out for validation",
+        config.ratio,
+        rehearsal.len(),
+        validation.len()
+    );
+
+    let buffer = ReplayBuffer::new(rehearsal, config.ratio);
+    Ok(match config.min_accuracy {
+        Some(min_accuracy) => buffer.with_validation(validation, min_accuracy),
+        None => buffer,
+    })
 }
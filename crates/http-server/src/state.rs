@@ -1,7 +1,32 @@
+use crate::auth::ApiKeys;
+use crate::classifier::BatchMetrics;
+use crate::limits::Limits;
+use crate::metrics::Metrics;
+use nn_engine::DigitClassifierService;
 use nn_engine::port::async_classifier::AsyncDigitClassifier;
+use nn_engine::port::feedback_queue::FeedbackQueue;
+use nn_engine::port::sample_store::SampleStore;
 use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub struct FeedbackState {
+    pub queue: Arc<dyn FeedbackQueue>,
+    /// Samples the model contradicts with at least this confidence are flagged.
+    pub flag_confidence: f32,
+    /// Held while samples are approved or rejected.
+    pub reviewing: Mutex<()>,
+}
 
 #[derive(Clone)]
 pub struct AppState {
     pub classifier: Arc<dyn AsyncDigitClassifier>,
+    pub batching: Arc<BatchMetrics>,
+    pub metrics: Arc<Metrics>,
+    /// Lifecycle of the served model, for health and introspection routes.
+    pub model: Arc<DigitClassifierService>,
+    pub auth: Arc<ApiKeys>,
+    pub limits: Arc<Limits>,
+    pub feedback: Arc<FeedbackState>,
+    /// Every sample the model was trained on, for offline retraining.
+    pub samples: Arc<dyn SampleStore>,
 }
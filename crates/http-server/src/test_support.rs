@@ -0,0 +1,166 @@
+//! Routers and requests shared by the handler and middleware tests.
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    middleware,
+    response::Response,
+};
+use http_body_util::BodyExt;
+use nn_engine::{
+    DigitClassifierService, JsonLinesFeedbackQueue, JsonLinesSampleStore, ReplayBuffer,
+    TrainingSample,
+};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tower::ServiceExt;
+
+use crate::auth::ApiKeys;
+use crate::classifier::BatchMetrics;
+use crate::limits::{Limits, LimitsConfig};
+use crate::metrics::{InstrumentedClassifier, Metrics};
+use crate::routes::router;
+use crate::state::{AppState, FeedbackState};
+
+pub const MODEL_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../assets/models/default.bin"
+);
+
+/// Temporary files, removed when dropped.
+#[derive(Default)]
+pub struct TempFiles(Vec<PathBuf>);
+
+impl TempFiles {
+    /// A fresh path per call, since tests run concurrently.
+    pub fn path(&mut self, name: &str) -> PathBuf {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "mnist_http_{}_{}_{}.jsonl",
+            name,
+            std::process::id(),
+            n
+        ));
+        let _ = std::fs::remove_file(&path);
+        self.0.push(path.clone());
+        path
+    }
+}
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        for path in &self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+pub async fn loaded_model() -> Arc<DigitClassifierService> {
+    let model = Arc::new(DigitClassifierService::from_path(MODEL_PATH));
+    model.load_model().await.unwrap();
+    model
+}
+
+/// The default model behind a replay guard no update can pass: blank
+/// images of every digit can't all be classified right.
+pub async fn strict_model() -> Arc<DigitClassifierService> {
+    let blank: Vec<TrainingSample> = (0..10)
+        .map(|label| TrainingSample {
+            label,
+            pixels: vec![0; 784],
+        })
+        .collect();
+    let model = DigitClassifierService::from_path(MODEL_PATH)
+        .with_replay(ReplayBuffer::new(blank.clone(), 4).with_validation(blank, 1.0));
+    model.load_model().await.unwrap();
+    Arc::new(model)
+}
+
+/// Keys `admin-key` and `trainer-key`; feedback and samples go to fresh
+/// temporary files, which live as long as the returned guard.
+pub fn state_with(
+    model: Arc<DigitClassifierService>,
+    limits: LimitsConfig,
+) -> (AppState, TempFiles) {
+    let mut files = TempFiles::default();
+    let metrics = Arc::new(Metrics::default());
+    let state = AppState {
+        classifier: Arc::new(InstrumentedClassifier::new(model.clone(), metrics.clone())),
+        batching: Arc::new(BatchMetrics::default()),
+        metrics,
+        model,
+        auth: Arc::new(ApiKeys::parse("admin:admin-key,trainer:trainer-key").unwrap()),
+        limits: Arc::new(Limits::new(limits)),
+        feedback: Arc::new(FeedbackState {
+            queue: Arc::new(JsonLinesFeedbackQueue::open(files.path("feedback")).unwrap()),
+            flag_confidence: 0.9,
+            reviewing: Default::default(),
+        }),
+        samples: Arc::new(JsonLinesSampleStore::new(files.path("samples"))),
+    };
+
+    (state, files)
+}
+
+pub fn app_with(model: Arc<DigitClassifierService>) -> Router {
+    app_with_limits(model, LimitsConfig::default())
+}
+
+/// The temporary files stay until the router and every response in flight
+/// are dropped.
+pub fn app_with_limits(model: Arc<DigitClassifierService>, limits: LimitsConfig) -> Router {
+    let (state, files) = state_with(model, limits);
+    let files = Arc::new(files);
+    router(state).layer(middleware::map_response(move |response: Response| {
+        let _files = &files;
+        async move { response }
+    }))
+}
+
+pub async fn app() -> Router {
+    app_with(loaded_model().await)
+}
+
+pub async fn post(app: Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    post_with_key(app, uri, None, body).await
+}
+
+pub async fn post_with_key(
+    app: Router,
+    uri: &str,
+    key: Option<&str>,
+    body: Value,
+) -> (StatusCode, Value) {
+    let mut request = Request::post(uri).header("content-type", "application/json");
+    if let Some(key) = key {
+        request = request.header("authorization", format!("Bearer {key}"));
+    }
+
+    send(app, request.body(Body::from(body.to_string())).unwrap()).await
+}
+
+pub async fn get(app: Router, uri: &str) -> (StatusCode, Value) {
+    get_with_key(app, uri, None).await
+}
+
+pub async fn get_with_key(app: Router, uri: &str, key: Option<&str>) -> (StatusCode, Value) {
+    let mut request = Request::get(uri);
+    if let Some(key) = key {
+        request = request.header("authorization", format!("Bearer {key}"));
+    }
+
+    send(app, request.body(Body::empty()).unwrap()).await
+}
+
+/// Status and JSON body of the response to `request`.
+pub async fn send(app: Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
@@ -0,0 +1,108 @@
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, Multipart, Request},
+    http::{StatusCode, header::CONTENT_TYPE},
+};
+use image::{ImageError, ImageReader, Limits, imageops::FilterType};
+use nn_engine::{GrayImage, MAX_IMAGE_SIDE};
+use std::io::Cursor;
+
+use crate::handlers::error::ApiError;
+
+/// Longer side photos are shrunk to before preprocessing; plenty for a
+/// 20×20 digit and keeps large uploads cheap.
+const MAX_SIDE: u32 = 512;
+
+/// Decoder buffers may not exceed a `MAX_IMAGE_SIDE`² RGBA image.
+const MAX_DECODE_BYTES: u64 = (MAX_IMAGE_SIDE * MAX_IMAGE_SIDE * 4) as u64;
+
+/// Encoded image from a `multipart/form-data` body (field `image`, or the
+/// first file) or from a raw body such as `Content-Type: image/png`.
+pub struct ImageUpload(pub Bytes);
+
+impl<S: Send + Sync> FromRequest<S> for ImageUpload {
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let is_multipart = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("multipart/form-data"));
+
+        if !is_multipart {
+            let bytes = Bytes::from_request(req, state)
+                .await
+                .map_err(|e| ApiError::new(e.status(), e.body_text()))?;
+            return Ok(Self(bytes));
+        }
+
+        let mut multipart = Multipart::from_request(req, state)
+            .await
+            .map_err(|e| ApiError::new(e.status(), e.body_text()))?;
+
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|e| ApiError::new(e.status(), e.body_text()))?
+        {
+            if field.name() == Some("image") || field.file_name().is_some() {
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|e| ApiError::new(e.status(), e.body_text()))?;
+                return Ok(Self(bytes));
+            }
+        }
+
+        Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "multipart body has no `image` field",
+        ))
+    }
+}
+
+/// Decodes PNG/JPEG/BMP into grayscale, flattening transparency onto white
+/// so a transparent PNG with dark strokes reads like ink on paper. Images
+/// wider or taller than `MAX_IMAGE_SIDE` are refused before decoding.
+pub fn decode_grayscale(bytes: &[u8]) -> Result<GrayImage, ApiError> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_SIDE as u32);
+    limits.max_image_height = Some(MAX_IMAGE_SIDE as u32);
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
+    reader.limits(limits);
+
+    let image = reader.decode().map_err(|e| match e {
+        ImageError::Unsupported(_) => {
+            ApiError::new(StatusCode::UNSUPPORTED_MEDIA_TYPE, e.to_string())
+        }
+        ImageError::Limits(_) => ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, e.to_string()),
+        _ => ApiError::new(StatusCode::BAD_REQUEST, e.to_string()),
+    })?;
+
+    let image = if image.width().max(image.height()) > MAX_SIDE {
+        image.resize(MAX_SIDE, MAX_SIDE, FilterType::Triangle)
+    } else {
+        image
+    };
+
+    let luma = image.to_luma_alpha8();
+    let pixels = luma
+        .pixels()
+        .map(|p| {
+            let [value, alpha] = p.0;
+            let alpha = alpha as u32;
+            ((value as u32 * alpha + 255 * (255 - alpha)) / 255) as u8
+        })
+        .collect();
+
+    Ok(GrayImage::new(
+        luma.width() as usize,
+        luma.height() as usize,
+        pixels,
+    )?)
+}
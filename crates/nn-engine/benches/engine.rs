@@ -0,0 +1,95 @@
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+
+use nn_engine::port::classifier::{BatchPredictor, BatchTrainer, DigitPredictor, DigitTrainer};
+use nn_engine::{NdArrayEngine, ParallelBatchTrainer, TrainingSample};
+
+const BATCH_SIZES: [usize; 3] = [1, 32, 128];
+
+fn sample_images(n: usize) -> Vec<Vec<u8>> {
+    (0..n)
+        .map(|i| (0..784).map(|p| ((p * 7 + i * 13) % 256) as u8).collect())
+        .collect()
+}
+
+fn sample_batch(n: usize) -> Vec<TrainingSample> {
+    sample_images(n)
+        .into_iter()
+        .enumerate()
+        .map(|(i, pixels)| TrainingSample {
+            label: (i % 10) as u8,
+            pixels,
+        })
+        .collect()
+}
+
+fn inference(c: &mut Criterion) {
+    let engine = NdArrayEngine::with_seed(42);
+    let mut group = c.benchmark_group("inference");
+
+    for size in BATCH_SIZES {
+        let images = sample_images(size);
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("predict_loop", size),
+            &images,
+            |b, images| {
+                b.iter(|| {
+                    for image in images {
+                        black_box(engine.predict(image).unwrap());
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("predict_batch", size),
+            &images,
+            |b, images| b.iter(|| black_box(engine.predict_batch(images))),
+        );
+    }
+
+    group.finish();
+}
+
+fn training(c: &mut Criterion) {
+    let mut group = c.benchmark_group("training");
+    group.sample_size(20);
+
+    for size in BATCH_SIZES {
+        let batch = sample_batch(size);
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("train_loop", size), &batch, |b, batch| {
+            let mut engine = NdArrayEngine::with_seed(42);
+            b.iter(|| {
+                for sample in batch {
+                    black_box(engine.train(sample.label, &sample.pixels).unwrap());
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("train_batch", size), &batch, |b, batch| {
+            let mut engine = NdArrayEngine::with_seed(42);
+            b.iter(|| black_box(engine.train_batch(batch).unwrap()))
+        });
+
+        for threads in [2, 4] {
+            let trainer = ParallelBatchTrainer::new(threads).unwrap();
+            group.bench_with_input(
+                BenchmarkId::new(format!("train_batch_parallel_{}", threads), size),
+                &batch,
+                |b, batch| {
+                    let mut engine = NdArrayEngine::with_seed(42);
+                    b.iter(|| black_box(trainer.train_batch(&mut engine, batch).unwrap()))
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, inference, training);
+criterion_main!(benches);
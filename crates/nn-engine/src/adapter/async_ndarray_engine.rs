@@ -1,85 +1,257 @@
 use async_trait::async_trait;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tokio::task;
 
 use crate::adapter::ndarray_engine::NdArrayEngine;
-use crate::domain::TrainingStepResult;
-use crate::domain::{ModelState, Prediction, error::NNError};
-use crate::port::classifier::{
-    DigitPredictor, DigitTrainer, ModelStateExporter, ModelStateImporter,
+use crate::adapter::parallel_trainer::ParallelBatchTrainer;
+use crate::domain::{
+    Activations, Attribution, AttributionMethod, ModelState, Prediction, error::NNError,
 };
+use crate::domain::{BatchTrainingResult, TrainingSample, TrainingStepResult};
 use crate::port::async_classifier::{
+    AsyncActivationExtractor, AsyncAttributionExplainer, AsyncBatchPredictor, AsyncBatchTrainer,
     AsyncDigitPredictor, AsyncDigitTrainer, AsyncModelStateExporter, AsyncModelStateImporter,
 };
+use crate::port::classifier::{
+    ActivationExtractor, AttributionExplainer, BatchPredictor, BatchTrainer, DigitPredictor,
+    DigitTrainer, ModelStateExporter, ModelStateImporter,
+};
+
+/// Time updates spent waiting for the working copy's lock.
+#[derive(Default)]
+pub struct LockWaitMetrics {
+    acquisitions: AtomicU64,
+    wait_ns_total: AtomicU64,
+    wait_ns_max: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LockWaitSnapshot {
+    pub acquisitions: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl LockWaitMetrics {
+    fn record(&self, wait: Duration) {
+        let ns = wait.as_nanos() as u64;
+
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+        self.wait_ns_total.fetch_add(ns, Ordering::Relaxed);
+        self.wait_ns_max.fetch_max(ns, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> LockWaitSnapshot {
+        LockWaitSnapshot {
+            acquisitions: self.acquisitions.load(Ordering::Relaxed),
+            total: Duration::from_nanos(self.wait_ns_total.load(Ordering::Relaxed)),
+            max: Duration::from_nanos(self.wait_ns_max.load(Ordering::Relaxed)),
+        }
+    }
+}
 
+/// Async facade over [`NdArrayEngine`].
+///
+/// Predictions run in parallel against an immutable published snapshot and
+/// never wait for training. Training and imports are serialised on a separate
+/// working copy, which is cloned and swapped into the snapshot after every
+/// update.
 pub struct AsyncNdArrayEngine {
-    inner: Arc<Mutex<NdArrayEngine>>,
+    snapshot: Arc<RwLock<Arc<NdArrayEngine>>>,
+    working: Arc<Mutex<NdArrayEngine>>,
+    trainer: Option<Arc<ParallelBatchTrainer>>,
+    lock_wait: Arc<LockWaitMetrics>,
 }
 
 impl AsyncNdArrayEngine {
     pub fn new(engine: NdArrayEngine) -> Self {
         Self {
-            inner: Arc::new(Mutex::new(engine)),
+            snapshot: Arc::new(RwLock::new(Arc::new(engine.clone()))),
+            working: Arc::new(Mutex::new(engine)),
+            trainer: None,
+            lock_wait: Arc::new(LockWaitMetrics::default()),
         }
     }
+
+    /// Splits every `train_batch` call across a pool of `threads` workers.
+    pub fn with_threads(engine: NdArrayEngine, threads: usize) -> Result<Self, NNError> {
+        Ok(Self {
+            trainer: Some(Arc::new(ParallelBatchTrainer::new(threads)?)),
+            ..Self::new(engine)
+        })
+    }
+
+    pub fn lock_wait(&self) -> Arc<LockWaitMetrics> {
+        self.lock_wait.clone()
+    }
+
+    fn current(&self) -> Result<Arc<NdArrayEngine>, NNError> {
+        self.snapshot
+            .read()
+            .map(|engine| engine.clone())
+            .map_err(|_| NNError::InternalError)
+    }
+
+    /// Runs `update` on the working copy and publishes the result.
+    async fn update<T, F>(&self, update: F) -> Result<T, NNError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut NdArrayEngine) -> Result<T, NNError> + Send + 'static,
+    {
+        let working = self.working.clone();
+        let snapshot = self.snapshot.clone();
+        let lock_wait = self.lock_wait.clone();
+
+        task::spawn_blocking(move || {
+            let started = Instant::now();
+            let mut engine = working.blocking_lock();
+            lock_wait.record(started.elapsed());
+            let result = update(&mut engine)?;
+
+            let published = Arc::new(engine.clone());
+            *snapshot.write().map_err(|_| NNError::InternalError)? = published;
+
+            Ok(result)
+        })
+        .await
+        .map_err(|_| NNError::InternalError)?
+    }
+
+    /// Trains a copy of the working engine on `batch` and keeps it only if
+    /// `accept` returns `Ok`, so rejected weights are never published.
+    pub async fn train_batch_if<F>(
+        &self,
+        batch: Vec<TrainingSample>,
+        accept: F,
+    ) -> Result<BatchTrainingResult, NNError>
+    where
+        F: FnOnce(&NdArrayEngine) -> Result<(), NNError> + Send + 'static,
+    {
+        let trainer = self.trainer.clone();
+
+        self.update(move |engine| {
+            let mut candidate = engine.clone();
+            let result = match trainer {
+                Some(trainer) => trainer.train_batch(&mut candidate, &batch),
+                None => candidate.train_batch(&batch),
+            }?;
+            accept(&candidate)?;
+
+            *engine = candidate;
+            Ok(result)
+        })
+        .await
+    }
 }
 
 #[async_trait]
 impl AsyncDigitPredictor for AsyncNdArrayEngine {
     async fn predict(&self, pixels: &[u8]) -> Result<Prediction, NNError> {
-        let engine = self.inner.clone();
+        let engine = self.current()?;
         let pixels = pixels.to_vec();
 
-        task::spawn_blocking(move || {
-            let engine = engine.blocking_lock();
-            engine.predict(&pixels)
-        })
-        .await
-        .map_err(|_| NNError::InternalError)?
+        task::spawn_blocking(move || engine.predict(&pixels))
+            .await
+            .map_err(|_| NNError::InternalError)?
+    }
+}
+
+#[async_trait]
+impl AsyncActivationExtractor for AsyncNdArrayEngine {
+    async fn activations(&self, pixels: &[u8]) -> Result<Activations, NNError> {
+        let engine = self.current()?;
+        let pixels = pixels.to_vec();
+
+        task::spawn_blocking(move || engine.activations(&pixels))
+            .await
+            .map_err(|_| NNError::InternalError)?
+    }
+}
+
+#[async_trait]
+impl AsyncAttributionExplainer for AsyncNdArrayEngine {
+    async fn explain(
+        &self,
+        pixels: &[u8],
+        class: u8,
+        method: AttributionMethod,
+    ) -> Result<Attribution, NNError> {
+        let engine = self.current()?;
+        let pixels = pixels.to_vec();
+
+        task::spawn_blocking(move || engine.explain(&pixels, class, method))
+            .await
+            .map_err(|_| NNError::InternalError)?
+    }
+}
+
+#[async_trait]
+impl AsyncBatchPredictor for AsyncNdArrayEngine {
+    async fn predict_batch(
+        &self,
+        batch: Vec<Vec<u8>>,
+    ) -> Result<Vec<Result<Prediction, NNError>>, NNError> {
+        let engine = self.current()?;
+
+        task::spawn_blocking(move || engine.predict_batch(&batch))
+            .await
+            .map_err(|_| NNError::InternalError)
     }
 }
 
 #[async_trait]
 impl AsyncDigitTrainer for AsyncNdArrayEngine {
     async fn train(&self, label: u8, pixels: &[u8]) -> Result<TrainingStepResult, NNError> {
-        let engine = self.inner.clone();
         let pixels = pixels.to_vec();
 
-        task::spawn_blocking(move || {
-            let mut engine = engine.blocking_lock();
-            engine.train(label, &pixels)
+        self.update(move |engine| engine.train(label, &pixels))
+            .await
+    }
+}
+
+#[async_trait]
+impl AsyncBatchTrainer for AsyncNdArrayEngine {
+    async fn train_batch(
+        &self,
+        batch: Vec<TrainingSample>,
+    ) -> Result<BatchTrainingResult, NNError> {
+        let trainer = self.trainer.clone();
+
+        self.update(move |engine| match trainer {
+            Some(trainer) => trainer.train_batch(engine, &batch),
+            None => engine.train_batch(&batch),
         })
         .await
-        .map_err(|_| NNError::InternalError)?
     }
 }
 
 #[async_trait]
 impl AsyncModelStateExporter for AsyncNdArrayEngine {
     async fn export_state(&self) -> Result<ModelState, NNError> {
-        let engine = self.inner.clone();
+        let engine = self.current()?;
 
-        task::spawn_blocking(move || {
-            let engine = engine.blocking_lock();
-            engine.export_state()
-        })
-        .await
-        .map_err(|_| NNError::InternalError)?
+        task::spawn_blocking(move || engine.export_state())
+            .await
+            .map_err(|_| NNError::InternalError)?
     }
 }
 
 #[async_trait]
 impl AsyncModelStateImporter for AsyncNdArrayEngine {
     async fn import_state(&self, state: ModelState) -> Result<(), NNError> {
-        let engine = self.inner.clone();
-
-        task::spawn_blocking(move || {
-            let mut engine = engine.blocking_lock();
-            engine.import_state(state)
+        // Imported into a copy, so a bad state never reaches the working
+        // engine or the published snapshot.
+        self.update(move |engine| {
+            let mut fresh = engine.clone();
+            fresh.import_state(state)?;
+
+            *engine = fresh;
+            Ok(())
         })
         .await
-        .map_err(|_| NNError::InternalError)?
     }
 }
 
@@ -164,4 +336,74 @@ mod tests {
         assert_eq!(prediction.digit, 7);
         assert!(prediction.confidence > 0.8);
     }
+
+    #[tokio::test]
+    async fn test_async_parallel_train_batch() {
+        let async_engine =
+            AsyncNdArrayEngine::with_threads(NdArrayEngine::with_seed(3), 4).unwrap();
+
+        let batch: Vec<TrainingSample> = (0..8)
+            .map(|_| TrainingSample {
+                label: 7,
+                pixels: sample_pixels(),
+            })
+            .collect();
+
+        let result = async_engine.train_batch(batch).await.unwrap();
+
+        assert_eq!(result.samples, 8);
+        assert!(result.loss > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_predict_does_not_wait_for_training() {
+        let async_engine = AsyncNdArrayEngine::new(NdArrayEngine::new());
+        let pixels = sample_pixels();
+
+        // Simulate a long-running training step holding the working copy.
+        let _guard = async_engine.working.lock().await;
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            async_engine.predict(&pixels),
+        )
+        .await
+        .expect("predict blocked on the training lock")
+        .unwrap();
+
+        assert!(result.digit <= 9);
+    }
+
+    #[tokio::test]
+    async fn test_updates_record_lock_wait() {
+        let async_engine = AsyncNdArrayEngine::new(NdArrayEngine::new());
+        let pixels = sample_pixels();
+
+        async_engine.train(1, &pixels).await.unwrap();
+        async_engine.train(2, &pixels).await.unwrap();
+        async_engine.predict(&pixels).await.unwrap();
+
+        let wait = async_engine.lock_wait().snapshot();
+        assert_eq!(wait.acquisitions, 2);
+        assert!(wait.max <= wait.total);
+    }
+
+    #[tokio::test]
+    async fn test_training_is_published_to_predictions() {
+        let async_engine = AsyncNdArrayEngine::new(NdArrayEngine::new());
+        let pixels = sample_pixels();
+
+        let before = async_engine.export_state().await.unwrap();
+        async_engine.train(4, &pixels).await.unwrap();
+        let after = async_engine.export_state().await.unwrap();
+
+        assert_ne!(before.w1, after.w1);
+
+        let mut expected = NdArrayEngine::new();
+        expected.import_state(after).unwrap();
+        assert_eq!(
+            async_engine.predict(&pixels).await.unwrap().confidence,
+            expected.predict(&pixels).unwrap().confidence
+        );
+    }
 }
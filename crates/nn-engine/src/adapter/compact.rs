@@ -0,0 +1,253 @@
+//! Compact binary checkpoint for the browser demo: an 8-byte header
+//! (`b"MNRS"`, format version, element type, two reserved bytes) followed by
+//! the tensors in [`MODEL_TENSORS`](crate::domain::MODEL_TENSORS) order as
+//! raw little-endian values.
+//!
+//! With [`CompactDType::Int8`] each weight matrix is stored as one `f32`
+//! scale per row followed by the `i8` values; biases stay `f32`. Loading
+//! always yields a float [`ModelState`], so the result can still be trained.
+
+use crate::adapter::quantized_engine::quantize_matrix;
+use crate::domain::{MODEL_TENSORS, ModelState, QuantizationScheme, error::NNError};
+
+const MAGIC: &[u8; 4] = b"MNRS";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompactDType {
+    F32,
+    F16,
+    Int8,
+}
+
+impl CompactDType {
+    fn tag(self) -> u8 {
+        match self {
+            CompactDType::F32 => 0,
+            CompactDType::F16 => 1,
+            CompactDType::Int8 => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, NNError> {
+        match tag {
+            0 => Ok(CompactDType::F32),
+            1 => Ok(CompactDType::F16),
+            2 => Ok(CompactDType::Int8),
+            _ => Err(NNError::SerializationError),
+        }
+    }
+}
+
+pub fn export_compact(state: &ModelState, dtype: CompactDType) -> Result<Vec<u8>, NNError> {
+    state.check_shapes()?;
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + state.size_bytes());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&[VERSION, dtype.tag(), 0, 0]);
+
+    for (_, shape, values) in state.tensors() {
+        match (dtype, shape) {
+            (CompactDType::F16, _) => {
+                bytes.extend(values.iter().flat_map(|v| f32_to_f16(*v).to_le_bytes()))
+            }
+            (CompactDType::Int8, [rows, cols]) => {
+                let (quantized, scales) =
+                    quantize_matrix(values, *rows, *cols, QuantizationScheme::PerChannel);
+                bytes.extend(scales.iter().flat_map(|s| s.to_le_bytes()));
+                bytes.extend(quantized.iter().map(|q| *q as u8));
+            }
+            _ => bytes.extend(values.iter().flat_map(|v| v.to_le_bytes())),
+        }
+    }
+
+    Ok(bytes)
+}
+
+pub fn import_compact(bytes: &[u8]) -> Result<ModelState, NNError> {
+    if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC || bytes[4] != VERSION {
+        return Err(NNError::SerializationError);
+    }
+    let dtype = CompactDType::from_tag(bytes[5])?;
+
+    let mut rest = &bytes[HEADER_LEN..];
+    let mut take = |len: usize| -> Result<&[u8], NNError> {
+        if rest.len() < len {
+            return Err(NNError::SerializationError);
+        }
+        let (head, tail) = rest.split_at(len);
+        rest = tail;
+        Ok(head)
+    };
+
+    let mut tensors = Vec::with_capacity(4);
+
+    for (_, shape) in MODEL_TENSORS {
+        let len: usize = shape.iter().product();
+
+        let values = match (dtype, shape) {
+            (CompactDType::F16, _) => take(2 * len)?
+                .chunks_exact(2)
+                .map(|b| f16_to_f32(u16::from_le_bytes([b[0], b[1]])))
+                .collect(),
+            (CompactDType::Int8, [rows, cols]) => {
+                let scales: Vec<f32> = take(4 * rows)?
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+                    .collect();
+                take(len)?
+                    .iter()
+                    .enumerate()
+                    .map(|(i, q)| *q as i8 as f32 * scales[i / cols])
+                    .collect()
+            }
+            _ => take(4 * len)?
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+                .collect(),
+        };
+
+        tensors.push(values);
+    }
+
+    if !rest.is_empty() {
+        return Err(NNError::SerializationError);
+    }
+
+    let mut tensors = tensors.into_iter();
+    let mut next = || tensors.next().unwrap();
+
+    Ok(ModelState {
+        w1: next(),
+        b1: next(),
+        w2: next(),
+        b2: next(),
+    })
+}
+
+/// IEEE 754 binary16 with round-to-nearest-even.
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exp == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let exp = exp - 127 + 15;
+
+    if exp >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    if exp <= 0 {
+        if exp < -10 {
+            return sign;
+        }
+        let full = mantissa | 0x80_0000;
+        let shift = (14 - exp) as u32;
+        let rounded = full + (1 << (shift - 1)) - 1 + ((full >> shift) & 1);
+        return sign | (rounded >> shift) as u16;
+    }
+
+    // A carry out of the mantissa correctly bumps the exponent.
+    let rounded = mantissa + 0xfff + ((mantissa >> 13) & 1);
+    let half = ((exp as u32) << 10) + (rounded >> 13);
+
+    sign | half.min(0x7c00) as u16
+}
+
+fn f16_to_f32(half: u16) -> f32 {
+    let sign = ((half & 0x8000) as u32) << 16;
+    let exp = ((half >> 10) & 0x1f) as u32;
+    let mantissa = (half & 0x3ff) as u32;
+
+    let bits = match exp {
+        0 => {
+            let value = mantissa as f32 * 2f32.powi(-24);
+            return if sign != 0 { -value } else { value };
+        }
+        0x1f => sign | 0x7f80_0000 | (mantissa << 13),
+        _ => sign | ((exp + 127 - 15) << 23) | (mantissa << 13),
+    };
+
+    f32::from_bits(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::ndarray_engine::NdArrayEngine;
+    use crate::port::classifier::{DigitPredictor, ModelStateExporter, ModelStateImporter};
+
+    #[test]
+    fn f32_round_trip_is_exact() {
+        let state = NdArrayEngine::with_seed(3).export_state().unwrap();
+
+        let bytes = export_compact(&state, CompactDType::F32).unwrap();
+
+        assert_eq!(bytes.len(), HEADER_LEN + state.size_bytes());
+        assert_eq!(import_compact(&bytes).unwrap(), state);
+    }
+
+    #[test]
+    fn smaller_dtypes_keep_predictions() {
+        let state = NdArrayEngine::with_seed(4).export_state().unwrap();
+        let mut float = NdArrayEngine::new();
+        float.import_state(state.clone()).unwrap();
+
+        for (dtype, max_size) in [
+            (CompactDType::F16, state.size_bytes() / 2 + 64),
+            (CompactDType::Int8, state.size_bytes() / 3),
+        ] {
+            let bytes = export_compact(&state, dtype).unwrap();
+            assert!(bytes.len() <= max_size, "{:?}: {}", dtype, bytes.len());
+
+            let mut engine = NdArrayEngine::new();
+            engine
+                .import_state(import_compact(&bytes).unwrap())
+                .unwrap();
+
+            let pixels: Vec<u8> = (0..784).map(|i| (i * 7 % 256) as u8).collect();
+            assert_eq!(
+                engine.predict(&pixels).unwrap().digit,
+                float.predict(&pixels).unwrap().digit,
+                "{:?}",
+                dtype
+            );
+        }
+    }
+
+    #[test]
+    fn f16_conversion_round_trips_every_value() {
+        for half in 0..=u16::MAX {
+            let value = f16_to_f32(half);
+            if value.is_nan() {
+                assert!(f16_to_f32(f32_to_f16(value)).is_nan());
+            } else {
+                assert_eq!(f32_to_f16(value), half, "{:#06x}", half);
+            }
+        }
+
+        assert_eq!(f32_to_f16(1.0 + f32::EPSILON), f32_to_f16(1.0));
+        assert_eq!(f32_to_f16(1e6), 0x7c00);
+        assert_eq!(f32_to_f16(1e-10), 0);
+    }
+
+    #[test]
+    fn rejects_truncated_or_foreign_data() {
+        let state = NdArrayEngine::with_seed(5).export_state().unwrap();
+        let bytes = export_compact(&state, CompactDType::F16).unwrap();
+
+        assert!(import_compact(&bytes[..bytes.len() - 1]).is_err());
+        assert!(import_compact(b"{\"w1\": []}").is_err());
+
+        let mut unknown_dtype = bytes.clone();
+        unknown_dtype[5] = 9;
+        assert!(import_compact(&unknown_dtype).is_err());
+    }
+}
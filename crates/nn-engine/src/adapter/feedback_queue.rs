@@ -0,0 +1,240 @@
+//! Feedback queue persisted as an append-only JSON lines log.
+//!
+//! Every submission and every approve/reject decision is one line; opening
+//! the file replays the log, so the queue survives restarts and the log
+//! doubles as an audit trail.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+use crate::adapter::metrics_sink::unix_time;
+use crate::domain::{Feedback, FeedbackStatus, error::NNError};
+use crate::port::feedback_queue::FeedbackQueue;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+enum Event {
+    Submitted(Feedback),
+    Resolved {
+        ids: Vec<u64>,
+        status: FeedbackStatus,
+    },
+}
+
+struct Log {
+    items: BTreeMap<u64, Feedback>,
+    next_id: u64,
+}
+
+impl Log {
+    fn apply(&mut self, event: Event) {
+        match event {
+            Event::Submitted(feedback) => {
+                self.next_id = self.next_id.max(feedback.id + 1);
+                self.items.insert(feedback.id, feedback);
+            }
+            Event::Resolved { ids, status } => {
+                for id in ids {
+                    if let Some(item) = self.items.get_mut(&id) {
+                        item.status = status;
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct JsonLinesFeedbackQueue {
+    path: PathBuf,
+    log: Mutex<Log>,
+}
+
+impl JsonLinesFeedbackQueue {
+    /// Opens or creates the log at `path`, replaying existing entries.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, NNError> {
+        let path = path.as_ref().to_path_buf();
+        let mut log = Log {
+            items: BTreeMap::new(),
+            next_id: 1,
+        };
+
+        match std::fs::File::open(&path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    let line = line.map_err(|e| NNError::IoError(e.to_string()))?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let event =
+                        serde_json::from_str(&line).map_err(|_| NNError::SerializationError)?;
+                    log.apply(event);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(NNError::IoError(e.to_string())),
+        }
+
+        Ok(Self {
+            path,
+            log: Mutex::new(log),
+        })
+    }
+
+    /// Appends `event` and syncs it to disk before it is applied in memory.
+    async fn append(&self, log: &mut Log, event: Event) -> Result<(), NNError> {
+        let mut line = serde_json::to_vec(&event).map_err(|_| NNError::SerializationError)?;
+        line.push(b'\n');
+
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            file.write_all(&line)?;
+            file.sync_data()
+        })
+        .await
+        .map_err(|_| NNError::InternalError)?
+        .map_err(|e| NNError::IoError(e.to_string()))?;
+
+        log.apply(event);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FeedbackQueue for JsonLinesFeedbackQueue {
+    async fn submit(&self, mut feedback: Feedback) -> Result<Feedback, NNError> {
+        let mut log = self.log.lock().await;
+
+        feedback.id = log.next_id;
+        feedback.submitted_at = unix_time();
+        feedback.status = FeedbackStatus::Pending;
+
+        self.append(&mut log, Event::Submitted(feedback.clone()))
+            .await?;
+        Ok(feedback)
+    }
+
+    async fn list(&self, status: Option<FeedbackStatus>) -> Result<Vec<Feedback>, NNError> {
+        let log = self.log.lock().await;
+
+        Ok(log
+            .items
+            .values()
+            .filter(|item| status.is_none_or(|status| item.status == status))
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: u64) -> Result<Option<Feedback>, NNError> {
+        Ok(self.log.lock().await.items.get(&id).cloned())
+    }
+
+    async fn resolve(&self, ids: &[u64], status: FeedbackStatus) -> Result<Vec<Feedback>, NNError> {
+        let mut log = self.log.lock().await;
+
+        let resolved: Vec<Feedback> = ids
+            .iter()
+            .filter_map(|id| log.items.get(id))
+            .filter(|item| item.status == FeedbackStatus::Pending)
+            .map(|item| Feedback {
+                status,
+                ..item.clone()
+            })
+            .collect();
+
+        if !resolved.is_empty() {
+            let ids = resolved.iter().map(|item| item.id).collect();
+            self.append(&mut log, Event::Resolved { ids, status })
+                .await?;
+        }
+
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Prediction;
+
+    fn feedback(label: u8) -> Feedback {
+        Feedback::new(
+            label,
+            vec![label; 784],
+            Prediction {
+                digit: 3,
+                confidence: 0.99,
+            },
+            0.9,
+        )
+    }
+
+    #[tokio::test]
+    async fn decisions_survive_reopening() {
+        let path = std::env::temp_dir().join(format!(
+            "mnist_feedback_queue_test_{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let queue = JsonLinesFeedbackQueue::open(&path).unwrap();
+        let first = queue.submit(feedback(1)).await.unwrap();
+        let second = queue.submit(feedback(3)).await.unwrap();
+        queue.submit(feedback(5)).await.unwrap();
+
+        assert_eq!((first.id, second.id), (1, 2));
+        assert!(first.flagged && !second.flagged);
+
+        let approved = queue
+            .resolve(&[first.id, 99], FeedbackStatus::Approved)
+            .await
+            .unwrap();
+        assert_eq!(approved.len(), 1);
+        assert_eq!(approved[0].label, 1);
+        queue
+            .resolve(&[second.id], FeedbackStatus::Rejected)
+            .await
+            .unwrap();
+
+        // Already resolved samples can't be approved twice.
+        let again = queue
+            .resolve(&[first.id], FeedbackStatus::Approved)
+            .await
+            .unwrap();
+        assert!(again.is_empty());
+        assert_eq!(
+            queue.get(first.id).await.unwrap().unwrap().status,
+            FeedbackStatus::Approved
+        );
+        assert!(queue.get(99).await.unwrap().is_none());
+
+        let reopened = JsonLinesFeedbackQueue::open(&path).unwrap();
+        let pending = reopened.list(Some(FeedbackStatus::Pending)).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].label, 5);
+        assert_eq!(reopened.list(None).await.unwrap().len(), 3);
+        assert_eq!(reopened.submit(feedback(7)).await.unwrap().id, 4);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_corrupt_log() {
+        let path = std::env::temp_dir().join(format!(
+            "mnist_feedback_queue_corrupt_{}.jsonl",
+            std::process::id()
+        ));
+        std::fs::write(&path, "not json\n").unwrap();
+
+        assert!(JsonLinesFeedbackQueue::open(&path).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
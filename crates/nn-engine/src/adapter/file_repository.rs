@@ -1,6 +1,10 @@
 use async_trait::async_trait;
 use tokio::fs;
 
+use crate::adapter::compact::{CompactDType, export_compact, import_compact};
+use crate::adapter::npz::{export_npz, import_npz};
+use crate::adapter::onnx::{export_onnx, import_onnx};
+use crate::adapter::safetensors::{export_safetensors, import_safetensors};
 use crate::domain::{ModelState, error::NNError};
 use crate::port::model_repository::ModelRepository;
 
@@ -33,7 +37,6 @@ impl ModelRepository for FileModelRepository {
     }
 }
 
-
 pub struct JsonModelRepository {
     path: String,
 }
@@ -59,10 +62,131 @@ impl ModelRepository for JsonModelRepository {
             .await
             .map_err(|_| NNError::PersistenceError)?;
 
-        serde_json::from_str(&json.as_str()).map_err(|_| NNError::PersistenceError)
+        serde_json::from_str(json.as_str()).map_err(|_| NNError::PersistenceError)
+    }
+}
+
+pub struct OnnxModelRepository {
+    path: String,
+}
+
+impl OnnxModelRepository {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl ModelRepository for OnnxModelRepository {
+    async fn save(&self, state: &ModelState) -> Result<(), NNError> {
+        let bytes = export_onnx(state)?;
+
+        fs::write(&self.path, bytes)
+            .await
+            .map_err(|_| NNError::PersistenceError)
+    }
+
+    async fn load(&self) -> Result<ModelState, NNError> {
+        let bytes = fs::read(&self.path)
+            .await
+            .map_err(|_| NNError::PersistenceError)?;
+
+        import_onnx(&bytes)
+    }
+}
+
+pub struct SafetensorsModelRepository {
+    path: String,
+}
+
+impl SafetensorsModelRepository {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl ModelRepository for SafetensorsModelRepository {
+    async fn save(&self, state: &ModelState) -> Result<(), NNError> {
+        let bytes = export_safetensors(state)?;
+
+        fs::write(&self.path, bytes)
+            .await
+            .map_err(|_| NNError::PersistenceError)
+    }
+
+    async fn load(&self) -> Result<ModelState, NNError> {
+        let bytes = fs::read(&self.path)
+            .await
+            .map_err(|_| NNError::PersistenceError)?;
+
+        import_safetensors(&bytes)
     }
 }
 
+pub struct NpzModelRepository {
+    path: String,
+}
+
+impl NpzModelRepository {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl ModelRepository for NpzModelRepository {
+    async fn save(&self, state: &ModelState) -> Result<(), NNError> {
+        let bytes = export_npz(state)?;
+
+        fs::write(&self.path, bytes)
+            .await
+            .map_err(|_| NNError::PersistenceError)
+    }
+
+    async fn load(&self) -> Result<ModelState, NNError> {
+        let bytes = fs::read(&self.path)
+            .await
+            .map_err(|_| NNError::PersistenceError)?;
+
+        import_npz(&bytes)
+    }
+}
+
+/// Compact binary checkpoint; `dtype` only affects saving, loading reads
+/// it from the file header.
+pub struct CompactModelRepository {
+    path: String,
+    dtype: CompactDType,
+}
+
+impl CompactModelRepository {
+    pub fn new(path: impl Into<String>, dtype: CompactDType) -> Self {
+        Self {
+            path: path.into(),
+            dtype,
+        }
+    }
+}
+
+#[async_trait]
+impl ModelRepository for CompactModelRepository {
+    async fn save(&self, state: &ModelState) -> Result<(), NNError> {
+        let bytes = export_compact(state, self.dtype)?;
+
+        fs::write(&self.path, bytes)
+            .await
+            .map_err(|_| NNError::PersistenceError)
+    }
+
+    async fn load(&self) -> Result<ModelState, NNError> {
+        let bytes = fs::read(&self.path)
+            .await
+            .map_err(|_| NNError::PersistenceError)?;
+
+        import_compact(&bytes)
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -107,6 +231,54 @@ mod tests {
         let _ = fs::remove_file(&path).await;
     }
 
+    #[tokio::test]
+    async fn onnx_save_and_load_success() {
+        let path = temp_file_path("model_test.onnx");
+        let _ = fs::remove_file(&path).await;
+
+        let repo = OnnxModelRepository::new(path.to_str().unwrap());
+        let state = test_state();
+
+        repo.save(&state).await.unwrap();
+        let loaded = repo.load().await.unwrap();
+
+        assert_eq!(state, loaded);
+
+        let _ = fs::remove_file(&path).await;
+    }
+
+    #[tokio::test]
+    async fn safetensors_save_and_load_success() {
+        let path = temp_file_path("model_test.safetensors");
+        let _ = fs::remove_file(&path).await;
+
+        let repo = SafetensorsModelRepository::new(path.to_str().unwrap());
+        let state = test_state();
+
+        repo.save(&state).await.unwrap();
+        let loaded = repo.load().await.unwrap();
+
+        assert_eq!(state, loaded);
+
+        let _ = fs::remove_file(&path).await;
+    }
+
+    #[tokio::test]
+    async fn npz_save_and_load_success() {
+        let path = temp_file_path("model_test.npz");
+        let _ = fs::remove_file(&path).await;
+
+        let repo = NpzModelRepository::new(path.to_str().unwrap());
+        let state = test_state();
+
+        repo.save(&state).await.unwrap();
+        let loaded = repo.load().await.unwrap();
+
+        assert_eq!(state, loaded);
+
+        let _ = fs::remove_file(&path).await;
+    }
+
     #[tokio::test]
     async fn load_non_existing_file_returns_error() {
         let path = temp_file_path("non_existing_model.bin");
@@ -0,0 +1,199 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::domain::{MetricsScope, TrainingMetrics, error::NNError};
+use crate::port::metrics_sink::MetricsSink;
+
+pub(crate) fn unix_time() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// Prints progress to stdout in the same format the trainer always used.
+pub struct ConsoleMetricsSink;
+
+impl MetricsSink for ConsoleMetricsSink {
+    fn record(&mut self, metrics: &TrainingMetrics) -> Result<(), NNError> {
+        match metrics.scope {
+            MetricsScope::Step => println!(
+                "Samples: {} | Avg Loss: {:.4} | Accuracy: {:.2}%",
+                metrics.samples,
+                metrics.loss,
+                100.0 * metrics.accuracy
+            ),
+            MetricsScope::Epoch => println!(
+                "\n📊 Epoch Result → Loss: {:.4} | Accuracy: {:.2}%",
+                metrics.loss,
+                100.0 * metrics.accuracy
+            ),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct JsonLinesRow<'a> {
+    timestamp: f64,
+    #[serde(flatten)]
+    metrics: &'a TrainingMetrics,
+}
+
+/// Writes one JSON object per line.
+pub struct JsonLinesMetricsSink {
+    writer: BufWriter<File>,
+}
+
+impl JsonLinesMetricsSink {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, NNError> {
+        let file = File::create(path).map_err(|e| NNError::IoError(e.to_string()))?;
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+}
+
+impl MetricsSink for JsonLinesMetricsSink {
+    fn record(&mut self, metrics: &TrainingMetrics) -> Result<(), NNError> {
+        let row = JsonLinesRow {
+            timestamp: unix_time(),
+            metrics,
+        };
+
+        serde_json::to_writer(&mut self.writer, &row).map_err(|_| NNError::SerializationError)?;
+        self.writer
+            .write_all(b"\n")
+            .map_err(|e| NNError::IoError(e.to_string()))
+    }
+
+    fn flush(&mut self) -> Result<(), NNError> {
+        self.writer
+            .flush()
+            .map_err(|e| NNError::IoError(e.to_string()))
+    }
+}
+
+/// Writes a CSV table with a `timestamp,scope,epoch,step,samples,loss,accuracy` header.
+pub struct CsvMetricsSink {
+    writer: csv::Writer<File>,
+}
+
+impl CsvMetricsSink {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, NNError> {
+        let mut writer =
+            csv::Writer::from_path(path).map_err(|e| NNError::IoError(e.to_string()))?;
+
+        writer
+            .write_record([
+                "timestamp",
+                "scope",
+                "epoch",
+                "step",
+                "samples",
+                "loss",
+                "accuracy",
+            ])
+            .map_err(|e| NNError::IoError(e.to_string()))?;
+
+        Ok(Self { writer })
+    }
+}
+
+impl MetricsSink for CsvMetricsSink {
+    fn record(&mut self, metrics: &TrainingMetrics) -> Result<(), NNError> {
+        self.writer
+            .write_record([
+                unix_time().to_string(),
+                metrics.scope.as_str().to_string(),
+                metrics.epoch.to_string(),
+                metrics.step.to_string(),
+                metrics.samples.to_string(),
+                metrics.loss.to_string(),
+                metrics.accuracy.to_string(),
+            ])
+            .map_err(|e| NNError::IoError(e.to_string()))
+    }
+
+    fn flush(&mut self) -> Result<(), NNError> {
+        self.writer
+            .flush()
+            .map_err(|e| NNError::IoError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// Prefixed with the process id so parallel test runs don't collide.
+    fn temp_file_path(name: &str) -> PathBuf {
+        let mut path = env::temp_dir();
+        path.push(format!("{}_{}", std::process::id(), name));
+        path
+    }
+
+    fn sample_metrics(scope: MetricsScope, step: usize) -> TrainingMetrics {
+        TrainingMetrics {
+            scope,
+            epoch: 1,
+            step,
+            samples: step,
+            loss: 0.5,
+            accuracy: 0.75,
+        }
+    }
+
+    #[test]
+    fn json_lines_writes_one_object_per_record() {
+        let path = temp_file_path("metrics_test.jsonl");
+
+        let mut sink = JsonLinesMetricsSink::create(&path).unwrap();
+        sink.record(&sample_metrics(MetricsScope::Step, 5000)).unwrap();
+        sink.record(&sample_metrics(MetricsScope::Epoch, 60000)).unwrap();
+        sink.flush().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["scope"], "step");
+        assert_eq!(lines[0]["step"], 5000);
+        assert_eq!(lines[1]["scope"], "epoch");
+        assert!(lines[1]["timestamp"].as_f64().unwrap() > 0.0);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn csv_writes_header_and_rows() {
+        let path = temp_file_path("metrics_test.csv");
+
+        let mut sink = CsvMetricsSink::create(&path).unwrap();
+        sink.record(&sample_metrics(MetricsScope::Step, 5000)).unwrap();
+        sink.flush().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let mut lines = content.lines();
+
+        assert_eq!(
+            lines.next().unwrap(),
+            "timestamp,scope,epoch,step,samples,loss,accuracy"
+        );
+        assert!(lines.next().unwrap().ends_with(",step,1,5000,5000,0.5,0.75"));
+        assert!(lines.next().is_none());
+
+        let _ = fs::remove_file(&path);
+    }
+}
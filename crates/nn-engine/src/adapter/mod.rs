@@ -1,6 +1,25 @@
 #[cfg(feature = "server")]
 pub mod async_ndarray_engine;
 #[cfg(feature = "server")]
+pub mod feedback_queue;
+#[cfg(feature = "server")]
 pub mod file_repository;
+#[cfg(feature = "server")]
+pub mod metrics_sink;
+#[cfg(feature = "server")]
+pub mod npz;
+#[cfg(feature = "server")]
+pub mod parallel_trainer;
+#[cfg(feature = "server")]
+pub mod png;
+#[cfg(feature = "server")]
+pub mod sample_store;
+#[cfg(feature = "server")]
+pub mod tensorboard_metrics_sink;
 
+pub mod compact;
 pub mod ndarray_engine;
+pub mod onnx;
+pub(crate) mod protobuf;
+pub mod quantized_engine;
+pub mod safetensors;
@@ -1,18 +1,122 @@
-use crate::domain::{ModelState, Prediction, TrainingStepResult, error::NNError};
+use crate::domain::{
+    Activations, Attribution, AttributionMethod, BatchTrainingResult, MNIST_SIDE, ModelState,
+    Prediction, TrainingSample, TrainingStepResult,
+    error::NNError,
+};
 use crate::port::classifier::{
-    DigitPredictor, DigitTrainer, ModelStateExporter, ModelStateImporter,
+    ActivationExtractor, AttributionExplainer, BatchPredictor, BatchTrainer, DigitPredictor,
+    DigitTrainer, ModelStateExporter, ModelStateImporter,
 };
 
-use ndarray::{Array1, Array2, Axis};
+use ndarray::linalg::{general_mat_mul, general_mat_vec_mul};
+use ndarray::{Array1, Array2, ArrayViewMut1, Axis, Zip};
 use ndarray_rand::RandomExt;
 use ndarray_rand::rand_distr::Normal;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Gradients of the loss summed over one or more samples.
+pub(crate) struct Gradients {
+    w1: Array2<f32>,
+    b1: Array1<f32>,
+    w2: Array2<f32>,
+    b2: Array1<f32>,
+    loss: f32,
+    correct: usize,
+    samples: usize,
+}
+
+impl Gradients {
+    fn zeros() -> Self {
+        Self {
+            w1: Array2::zeros((128, 784)),
+            b1: Array1::zeros(128),
+            w2: Array2::zeros((10, 128)),
+            b2: Array1::zeros(10),
+            loss: 0.0,
+            correct: 0,
+            samples: 0,
+        }
+    }
+
+    #[cfg_attr(not(feature = "server"), allow(dead_code))]
+    pub(crate) fn accumulate(&mut self, other: &Gradients) {
+        self.w1 += &other.w1;
+        self.b1 += &other.b1;
+        self.w2 += &other.w2;
+        self.b2 += &other.b2;
+        self.loss += other.loss;
+        self.correct += other.correct;
+        self.samples += other.samples;
+    }
 
+    pub(crate) fn result(&self) -> BatchTrainingResult {
+        BatchTrainingResult {
+            loss: self.loss / self.samples.max(1) as f32,
+            correct: self.correct,
+            samples: self.samples,
+        }
+    }
+}
+
+/// Per-sample activations and errors, reused across steps so the hot loop
+/// does not allocate.
+#[derive(Clone, Default)]
+struct Workspace {
+    input: Array1<f32>,
+    z1: Array1<f32>,
+    a1: Array1<f32>,
+    z2: Array1<f32>,
+    output: Array1<f32>,
+    dz1: Array1<f32>,
+    dz2: Array1<f32>,
+}
+
+impl Workspace {
+    fn new() -> Self {
+        Self {
+            input: Array1::zeros(784),
+            z1: Array1::zeros(128),
+            a1: Array1::zeros(128),
+            z2: Array1::zeros(10),
+            output: Array1::zeros(10),
+            dz1: Array1::zeros(128),
+            dz2: Array1::zeros(10),
+        }
+    }
+
+    fn load(&mut self, pixels: &[u8]) -> Result<(), NNError> {
+        if pixels.len() != 784 {
+            return Err(NNError::InvalidInput);
+        }
+
+        Zip::from(&mut self.input)
+            .and(pixels)
+            .for_each(|x, &p| *x = p as f32 / 255.0);
+
+        Ok(())
+    }
+
+    fn predicted(&self) -> (usize, f32) {
+        let (digit, confidence) = self
+            .output
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+
+        (digit, *confidence)
+    }
+}
+
+#[derive(Clone)]
 pub struct NdArrayEngine {
     w1: Array2<f32>,
     b1: Array1<f32>,
     w2: Array2<f32>,
     b2: Array1<f32>,
     lr: f32,
+    workspace: Workspace,
 }
 
 impl NdArrayEngine {
@@ -26,32 +130,131 @@ impl NdArrayEngine {
             w2: Array2::random((10, 128), Normal::new(0.0, he2).unwrap()),
             b2: Array1::zeros(10),
             lr: 0.01,
+            workspace: Workspace::new(),
         }
     }
 
-    fn normalize(pixels: &[u8]) -> Result<Array1<f32>, NNError> {
-        if pixels.len() != 784 {
-            return Err(NNError::InvalidInput);
+    /// Same as [`NdArrayEngine::new`], but with reproducible initial weights.
+    pub fn with_seed(seed: u64) -> Self {
+        let he1 = (2.0f32 / 784.0).sqrt();
+        let he2 = (2.0f32 / 128.0).sqrt();
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        Self {
+            w1: Array2::random_using((128, 784), Normal::new(0.0, he1).unwrap(), &mut rng),
+            b1: Array1::zeros(128),
+            w2: Array2::random_using((10, 128), Normal::new(0.0, he2).unwrap(), &mut rng),
+            b2: Array1::zeros(10),
+            lr: 0.01,
+            workspace: Workspace::new(),
+        }
+    }
+
+    pub fn with_learning_rate(mut self, lr: f32) -> Self {
+        self.lr = lr;
+        self
+    }
+
+    /// Sums the gradients of `samples` in order, without touching the weights.
+    pub(crate) fn gradients(&self, samples: &[TrainingSample]) -> Result<Gradients, NNError> {
+        let mut total = Gradients::zeros();
+        let mut ws = Workspace::new();
+
+        for sample in samples {
+            ws.load(&sample.pixels)?;
+            let (loss, correct) = self.backward(sample.label, &mut ws)?;
+
+            // Outer products are accumulated in place instead of materialised.
+            general_mat_mul(
+                1.0,
+                &ws.dz2.view().insert_axis(Axis(1)),
+                &ws.a1.view().insert_axis(Axis(0)),
+                1.0,
+                &mut total.w2,
+            );
+            total.b2 += &ws.dz2;
+
+            general_mat_mul(
+                1.0,
+                &ws.dz1.view().insert_axis(Axis(1)),
+                &ws.input.view().insert_axis(Axis(0)),
+                1.0,
+                &mut total.w1,
+            );
+            total.b1 += &ws.dz1;
+
+            total.loss += loss;
+            total.correct += correct as usize;
+            total.samples += 1;
         }
 
-        Ok(Array1::from(
-            pixels.iter().map(|p| *p as f32 / 255.0).collect::<Vec<_>>(),
-        ))
+        Ok(total)
     }
 
-    fn relu(x: &Array1<f32>) -> Array1<f32> {
-        x.map(|v| v.max(0.0))
+    /// Applies one gradient descent step using the mean of the accumulated gradients.
+    pub(crate) fn apply_gradients(&mut self, grads: &Gradients) {
+        if grads.samples == 0 {
+            return;
+        }
+
+        let scale = self.lr / grads.samples as f32;
+
+        self.w2.scaled_add(-scale, &grads.w2);
+        self.b2.scaled_add(-scale, &grads.b2);
+
+        self.w1.scaled_add(-scale, &grads.w1);
+        self.b1.scaled_add(-scale, &grads.b1);
     }
 
-    fn relu_derivative(x: &Array1<f32>) -> Array1<f32> {
-        x.map(|v| if *v > 0.0 { 1.0 } else { 0.0 })
+    fn forward(&self, ws: &mut Workspace) {
+        ws.z1.assign(&self.b1);
+        general_mat_vec_mul(1.0, &self.w1, &ws.input, 1.0, &mut ws.z1);
+        Zip::from(&mut ws.a1)
+            .and(&ws.z1)
+            .for_each(|a, &z| *a = z.max(0.0));
+
+        ws.z2.assign(&self.b2);
+        general_mat_vec_mul(1.0, &self.w2, &ws.a1, 1.0, &mut ws.z2);
+
+        ws.output.assign(&ws.z2);
+        Self::softmax(ws.output.view_mut());
     }
 
-    fn softmax(x: &Array1<f32>) -> Array1<f32> {
+    /// Runs the forward pass and fills `dz1`/`dz2`; returns the loss and
+    /// whether the prediction was correct.
+    fn backward(&self, label: u8, ws: &mut Workspace) -> Result<(f32, bool), NNError> {
+        if label > 9 {
+            return Err(NNError::InvalidInput);
+        }
+
+        // -------- Forward pass --------
+        self.forward(ws);
+
+        // -------- Backpropagation --------
+
+        // Output error (softmax + cross entropy derivative)
+        ws.dz2.assign(&ws.output);
+        ws.dz2[label as usize] -= 1.0;
+
+        // Hidden layer error
+        general_mat_vec_mul(1.0, &self.w2.t(), &ws.dz2, 0.0, &mut ws.dz1);
+        Zip::from(&mut ws.dz1).and(&ws.z1).for_each(|d, &z| {
+            if z <= 0.0 {
+                *d = 0.0;
+            }
+        });
+
+        let loss = Self::cross_entropy(&ws.output, label);
+        let (predicted, _) = ws.predicted();
+
+        Ok((loss, predicted == label as usize))
+    }
+
+    fn softmax(mut x: ArrayViewMut1<f32>) {
         let max = x.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
-        let exp = x.map(|v| (v - max).exp());
-        let sum = exp.sum();
-        exp / sum
+        x.mapv_inplace(|v| (v - max).exp());
+        let sum = x.sum();
+        x /= sum;
     }
 
     fn cross_entropy(output: &Array1<f32>, label: u8) -> f32 {
@@ -60,90 +263,250 @@ impl NdArrayEngine {
     }
 }
 
+impl Default for NdArrayEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DigitPredictor for NdArrayEngine {
     fn predict(&self, pixels: &[u8]) -> Result<Prediction, NNError> {
-        let input = Self::normalize(pixels)?;
-
-        let z1 = self.w1.dot(&input) + &self.b1;
-        let a1 = Self::relu(&z1);
+        let mut ws = Workspace::new();
+        ws.load(pixels)?;
 
-        let z2 = self.w2.dot(&a1) + &self.b2;
-        let output = Self::softmax(&z2);
+        self.forward(&mut ws);
 
-        let (digit, confidence) = output
-            .iter()
-            .enumerate()
-            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
-            .unwrap();
+        let (digit, confidence) = ws.predicted();
 
         Ok(Prediction {
             digit: digit as u8,
-            confidence: *confidence,
+            confidence,
         })
     }
 }
 
-impl DigitTrainer for NdArrayEngine {
-    fn train(&mut self, label: u8, pixels: &[u8]) -> Result<TrainingStepResult, NNError> {
-        let input = Self::normalize(pixels)?;
+impl BatchPredictor for NdArrayEngine {
+    fn predict_batch(&self, batch: &[Vec<u8>]) -> Vec<Result<Prediction, NNError>> {
+        // A matrix product over a single row is slower than the vector path.
+        if let [pixels] = batch {
+            return vec![self.predict(pixels)];
+        }
 
-        // -------- Forward pass --------
-        let z1 = self.w1.dot(&input) + &self.b1;
-        let a1 = Self::relu(&z1);
+        let valid: Vec<usize> = (0..batch.len())
+            .filter(|&i| batch[i].len() == 784)
+            .collect();
 
-        let z2 = self.w2.dot(&a1) + &self.b2;
-        let output = Self::softmax(&z2);
+        let mut input = Array2::<f32>::zeros((valid.len(), 784));
+        for (mut row, &i) in input.rows_mut().into_iter().zip(&valid) {
+            Zip::from(&mut row)
+                .and(&batch[i][..])
+                .for_each(|x, &p| *x = p as f32 / 255.0);
+        }
 
-        // -------- Target vector --------
-        let mut target = Array1::<f32>::zeros(10);
-        target[label as usize] = 1.0;
+        let mut a1 = Array2::<f32>::zeros((valid.len(), 128));
+        a1.assign(&self.b1);
+        general_mat_mul(1.0, &input, &self.w1.t(), 1.0, &mut a1);
+        a1.mapv_inplace(|v| v.max(0.0));
 
-        // -------- Backpropagation --------
+        let mut output = Array2::<f32>::zeros((valid.len(), 10));
+        output.assign(&self.b2);
+        general_mat_mul(1.0, &a1, &self.w2.t(), 1.0, &mut output);
 
-        // Output error (softmax + cross entropy derivative)
-        let dz2 = &output - &target;
+        let mut results: Vec<Result<Prediction, NNError>> =
+            vec![Err(NNError::InvalidInput); batch.len()];
 
-        let dw2 = dz2
-            .view()
-            .insert_axis(Axis(1))
-            .dot(&a1.view().insert_axis(Axis(0)));
+        for (mut row, &i) in output.rows_mut().into_iter().zip(&valid) {
+            Self::softmax(row.view_mut());
 
-        let db2 = dz2.clone();
+            let (digit, confidence) = row
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap();
 
-        // Hidden layer error
-        let da1 = self.w2.t().dot(&dz2);
-        let dz1 = da1 * Self::relu_derivative(&z1);
+            results[i] = Ok(Prediction {
+                digit: digit as u8,
+                confidence: *confidence,
+            });
+        }
 
-        let dw1 = dz1
-            .view()
-            .insert_axis(Axis(1))
-            .dot(&input.view().insert_axis(Axis(0)));
+        results
+    }
+}
+
+impl DigitTrainer for NdArrayEngine {
+    fn train(&mut self, label: u8, pixels: &[u8]) -> Result<TrainingStepResult, NNError> {
+        let mut ws = std::mem::take(&mut self.workspace);
+        let result = self.step(label, pixels, &mut ws);
+        self.workspace = ws;
+
+        result
+    }
+}
 
-        let db1 = dz1.clone();
+impl NdArrayEngine {
+    fn step(
+        &mut self,
+        label: u8,
+        pixels: &[u8],
+        ws: &mut Workspace,
+    ) -> Result<TrainingStepResult, NNError> {
+        ws.load(pixels)?;
+        let (loss, correct) = self.backward(label, ws)?;
 
         // -------- Gradient descent --------
+        // Rank-1 updates straight into the weights, no gradient matrices.
+        general_mat_mul(
+            -self.lr,
+            &ws.dz2.view().insert_axis(Axis(1)),
+            &ws.a1.view().insert_axis(Axis(0)),
+            1.0,
+            &mut self.w2,
+        );
+        self.b2.scaled_add(-self.lr, &ws.dz2);
+
+        general_mat_mul(
+            -self.lr,
+            &ws.dz1.view().insert_axis(Axis(1)),
+            &ws.input.view().insert_axis(Axis(0)),
+            1.0,
+            &mut self.w1,
+        );
+        self.b1.scaled_add(-self.lr, &ws.dz1);
+
+        let train_metrics = TrainingStepResult { loss, correct };
 
-        self.w2 -= &(dw2 * self.lr);
-        self.b2 -= &(db2 * self.lr);
+        Ok(train_metrics)
+    }
+}
 
-        self.w1 -= &(dw1 * self.lr);
-        self.b1 -= &(db1 * self.lr);
+impl BatchTrainer for NdArrayEngine {
+    fn train_batch(&mut self, batch: &[TrainingSample]) -> Result<BatchTrainingResult, NNError> {
+        if batch.is_empty() {
+            return Err(NNError::InvalidInput);
+        }
 
-        let loss = Self::cross_entropy(&output, label);
+        let grads = self.gradients(batch)?;
+        self.apply_gradients(&grads);
 
-        let predicted = output
-            .iter()
-            .enumerate()
-            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
-            .unwrap()
-            .0 as u8;
+        Ok(grads.result())
+    }
+}
 
-        let train_metrics = TrainingStepResult {
-            loss,
-            correct: predicted == label,
+impl NdArrayEngine {
+    /// Logit of `class` for the input loaded in `ws`.
+    fn class_score(&self, ws: &mut Workspace, class: usize) -> f32 {
+        self.forward(ws);
+        ws.z2[class]
+    }
+
+    /// Gradient of the logit of `class` with respect to the input in `ws`.
+    fn input_gradient(&self, ws: &mut Workspace, class: usize, gradient: &mut Array1<f32>) {
+        self.forward(ws);
+
+        Zip::from(&mut ws.dz1)
+            .and(self.w2.row(class))
+            .and(&ws.z1)
+            .for_each(|d, &w, &z| *d = if z > 0.0 { w } else { 0.0 });
+        general_mat_vec_mul(1.0, &self.w1.t(), &ws.dz1, 0.0, gradient);
+    }
+}
+
+impl ActivationExtractor for NdArrayEngine {
+    fn activations(&self, pixels: &[u8]) -> Result<Activations, NNError> {
+        let mut ws = Workspace::new();
+        ws.load(pixels)?;
+        self.forward(&mut ws);
+
+        Ok(Activations {
+            hidden: ws.a1.to_vec(),
+            logits: ws.z2.to_vec(),
+            probabilities: ws.output.to_vec(),
+        })
+    }
+}
+
+impl AttributionExplainer for NdArrayEngine {
+    fn explain(
+        &self,
+        pixels: &[u8],
+        class: u8,
+        method: AttributionMethod,
+    ) -> Result<Attribution, NNError> {
+        if class > 9 {
+            return Err(NNError::InvalidInput);
+        }
+        let mut ws = Workspace::new();
+        ws.load(pixels)?;
+        let input = ws.input.clone();
+        let target = class as usize;
+        let mut gradient = Array1::zeros(784);
+
+        let values = match method {
+            AttributionMethod::Saliency => {
+                self.input_gradient(&mut ws, target, &mut gradient);
+                gradient.mapv(f32::abs)
+            }
+            AttributionMethod::IntegratedGradients { steps } => {
+                if steps == 0 {
+                    return Err(NNError::InvalidInput);
+                }
+
+                // Midpoint Riemann sum over the straight path from black.
+                let mut total = Array1::<f32>::zeros(784);
+                for step in 0..steps {
+                    let alpha = (step as f32 + 0.5) / steps as f32;
+                    Zip::from(&mut ws.input)
+                        .and(&input)
+                        .for_each(|x, &v| *x = alpha * v);
+                    self.input_gradient(&mut ws, target, &mut gradient);
+                    total += &gradient;
+                }
+
+                total * &input / steps as f32
+            }
+            AttributionMethod::Occlusion { patch, stride } => {
+                if patch == 0 || stride == 0 {
+                    return Err(NNError::InvalidInput);
+                }
+                let patch = patch.min(MNIST_SIDE);
+                let last = MNIST_SIDE - patch;
+                let mut starts: Vec<usize> = (0..=last).step_by(stride).collect();
+                if starts.last() != Some(&last) {
+                    starts.push(last);
+                }
+
+                let score = self.class_score(&mut ws, target);
+                let mut drop = Array1::<f32>::zeros(784);
+                let mut covered = Array1::<f32>::zeros(784);
+
+                for &top in &starts {
+                    for &left in &starts {
+                        ws.input.assign(&input);
+                        let square = (top..top + patch).flat_map(|row| {
+                            (left..left + patch).map(move |col| row * MNIST_SIDE + col)
+                        });
+                        for i in square.clone() {
+                            ws.input[i] = 0.0;
+                        }
+
+                        let delta = score - self.class_score(&mut ws, target);
+                        for i in square {
+                            drop[i] += delta;
+                            covered[i] += 1.0;
+                        }
+                    }
+                }
+
+                drop / covered
+            }
         };
 
-        Ok(train_metrics)
+        Ok(Attribution {
+            method,
+            class,
+            values: values.to_vec(),
+        })
     }
 }
 
@@ -160,6 +523,11 @@ impl ModelStateExporter for NdArrayEngine {
 
 impl ModelStateImporter for NdArrayEngine {
     fn import_state(&mut self, state: ModelState) -> Result<(), NNError> {
+        // All tensors are checked up front so a bad state leaves the engine
+        // untouched.
+        state.check_shapes()?;
+        state.check_finite()?;
+
         self.w1 = Array2::from_shape_vec((128, 784), state.w1)
             .map_err(|_| NNError::SerializationError)?;
 
@@ -242,6 +610,131 @@ mod tests {
         assert!(prediction.confidence > 0.8);
     }
 
+    #[tokio::test]
+    async fn test_predict_batch_matches_single_predictions() {
+        let engine = NdArrayEngine::new();
+        let batch = vec![
+            sample_pixels(),
+            vec![1u8; 10],
+            (0..784).map(|p| (p % 256) as u8).collect(),
+        ];
+
+        let results = engine.predict_batch(&batch);
+
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[1], Err(NNError::InvalidInput)));
+
+        for i in [0, 2] {
+            let single = engine.predict(&batch[i]).unwrap();
+            let batched = results[i].as_ref().unwrap();
+
+            assert_eq!(single.digit, batched.digit);
+            assert!((single.confidence - batched.confidence).abs() < 1e-5);
+        }
+    }
+
+    fn score(engine: &NdArrayEngine, pixels: &[u8], class: usize) -> f32 {
+        let mut ws = Workspace::new();
+        ws.load(pixels).unwrap();
+        engine.class_score(&mut ws, class)
+    }
+
+    #[test]
+    fn test_saliency_matches_finite_differences() {
+        let engine = NdArrayEngine::with_seed(3);
+        let pixels = sample_pixels();
+        let mut ws = Workspace::new();
+        ws.load(&pixels).unwrap();
+        let mut gradient = Array1::zeros(784);
+        engine.input_gradient(&mut ws, 4, &mut gradient);
+
+        let saliency = engine
+            .explain(&pixels, 4, AttributionMethod::Saliency)
+            .unwrap();
+        assert_eq!(saliency.values.len(), 784);
+
+        for i in [0, 200, 407, 783] {
+            let eps = 1e-2;
+            let mut ws = Workspace::new();
+            ws.load(&pixels).unwrap();
+            ws.input[i] += eps;
+            let up = engine.class_score(&mut ws, 4);
+            ws.input[i] -= 2.0 * eps;
+            let down = engine.class_score(&mut ws, 4);
+
+            let numeric = (up - down) / (2.0 * eps);
+            assert!((numeric - gradient[i]).abs() < 1e-2);
+            assert_eq!(saliency.values[i], gradient[i].abs());
+        }
+    }
+
+    #[test]
+    fn test_integrated_gradients_sum_to_score_difference() {
+        let engine = NdArrayEngine::with_seed(3);
+        let pixels = sample_pixels();
+
+        let attribution = engine
+            .explain(
+                &pixels,
+                7,
+                AttributionMethod::IntegratedGradients { steps: 256 },
+            )
+            .unwrap();
+
+        let total: f32 = attribution.values.iter().sum();
+        let expected = score(&engine, &pixels, 7) - score(&engine, &[0; 784], 7);
+        assert!((total - expected).abs() < 0.02 * expected.abs().max(1.0));
+    }
+
+    #[test]
+    fn test_occlusion_ignores_black_regions() {
+        let engine = NdArrayEngine::with_seed(3);
+        let mut pixels = vec![0u8; 784];
+        // A bright block in the top-left corner only.
+        for row in 0..6 {
+            for col in 0..6 {
+                pixels[row * 28 + col] = 255;
+            }
+        }
+
+        let attribution = engine
+            .explain(
+                &pixels,
+                1,
+                AttributionMethod::Occlusion {
+                    patch: 4,
+                    stride: 4,
+                },
+            )
+            .unwrap();
+
+        // Patches with no ink change nothing.
+        assert_eq!(attribution.values[27 * 28 + 27], 0.0);
+        assert!(attribution.values[0] != 0.0);
+        assert_eq!(
+            engine.explain(&pixels, 10, AttributionMethod::Saliency),
+            Err(NNError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn test_activations_match_prediction() {
+        let engine = NdArrayEngine::with_seed(3);
+        let pixels = sample_pixels();
+
+        let activations = engine.activations(&pixels).unwrap();
+        let prediction = engine.predict(&pixels).unwrap();
+
+        assert_eq!(activations.hidden.len(), 128);
+        assert!(activations.hidden.iter().all(|&a| a >= 0.0));
+        assert_eq!(activations.logits.len(), 10);
+        assert_eq!(
+            activations.probabilities[prediction.digit as usize],
+            prediction.confidence
+        );
+        assert_eq!(engine.activations(&[0; 3]), Err(NNError::InvalidInput));
+    }
+
     #[tokio::test]
     async fn test_export_import_consistency() {
         let mut engine = NdArrayEngine::new();
@@ -259,4 +752,29 @@ mod tests {
 
         assert_eq!(p1.digit, p2.digit);
     }
+
+    #[test]
+    fn test_train_batch_rejects_empty_batch() {
+        let mut engine = NdArrayEngine::new();
+        let before = engine.export_state().unwrap();
+
+        assert_eq!(engine.train_batch(&[]).unwrap_err(), NNError::InvalidInput);
+        assert_eq!(engine.export_state().unwrap(), before);
+    }
+
+    #[test]
+    fn test_import_rejects_bad_bias_without_changes() {
+        let mut engine = NdArrayEngine::new();
+        let before = engine.export_state().unwrap();
+        let mut state = before.clone();
+        state.b1.truncate(1);
+
+        assert_eq!(engine.import_state(state), Err(NNError::SerializationError));
+        assert_eq!(engine.export_state().unwrap(), before);
+
+        let mut state = before.clone();
+        state.w2[3] = f32::NAN;
+        assert_eq!(engine.import_state(state), Err(NNError::SerializationError));
+        assert_eq!(engine.export_state().unwrap(), before);
+    }
 }
@@ -0,0 +1,227 @@
+//! NumPy `.npz` encoding of [`ModelState`]: a zip archive with one `.npy`
+//! array per tensor (`w1.npy`, `b1.npy`, ...), loadable with `np.load`.
+//!
+//! Arrays are written as little-endian `float32` in C order; `float64` and
+//! Fortran-ordered arrays are accepted on load.
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+
+use zip::{CompressionMethod, ZipArchive, ZipWriter, write::FileOptions};
+
+use crate::domain::{ModelState, error::NNError};
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+pub fn export_npz(state: &ModelState) -> Result<Vec<u8>, NNError> {
+    state.check_shapes()?;
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+
+    for (name, shape, values) in state.tensors() {
+        writer
+            .start_file(format!("{}.npy", name), options)
+            .map_err(|_| NNError::SerializationError)?;
+        writer
+            .write_all(&encode_npy(shape, values))
+            .map_err(|_| NNError::SerializationError)?;
+    }
+
+    let cursor = writer.finish().map_err(|_| NNError::SerializationError)?;
+    Ok(cursor.into_inner())
+}
+
+pub fn import_npz(bytes: &[u8]) -> Result<ModelState, NNError> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|_| NNError::SerializationError)?;
+    let mut tensors = HashMap::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|_| NNError::SerializationError)?;
+        let Some(name) = file.name().strip_suffix(".npy").map(str::to_string) else {
+            continue;
+        };
+
+        let mut npy = Vec::new();
+        file.read_to_end(&mut npy)
+            .map_err(|_| NNError::SerializationError)?;
+
+        tensors.insert(name, decode_npy(&npy)?);
+    }
+
+    ModelState::from_tensors(tensors)
+}
+
+/// Format version 1.0: magic, version, `u16` header length, then a Python
+/// dict literal padded so the data starts 64-byte aligned.
+fn encode_npy(shape: &[usize], values: &[f32]) -> Vec<u8> {
+    let dims: Vec<String> = shape.iter().map(|d| d.to_string()).collect();
+    let shape = match dims.as_slice() {
+        [single] => format!("({},)", single),
+        _ => format!("({})", dims.join(", ")),
+    };
+
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': {}, }}",
+        shape
+    );
+    let unpadded = NPY_MAGIC.len() + 4 + header.len() + 1;
+    header.push_str(&" ".repeat(unpadded.next_multiple_of(64) - unpadded));
+    header.push('\n');
+
+    let mut bytes = Vec::with_capacity(NPY_MAGIC.len() + 4 + header.len() + 4 * values.len());
+    bytes.extend_from_slice(NPY_MAGIC);
+    bytes.extend_from_slice(&[1, 0]);
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    bytes.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+    bytes
+}
+
+fn decode_npy(bytes: &[u8]) -> Result<(Vec<usize>, Vec<f32>), NNError> {
+    if !bytes.starts_with(NPY_MAGIC) || bytes.len() < 10 {
+        return Err(NNError::SerializationError);
+    }
+
+    // Versions 2.0+ widen the header length to `u32`.
+    let (header_len, header_start) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        _ => {
+            let len = bytes.get(8..12).ok_or(NNError::SerializationError)?;
+            (u32::from_le_bytes(len.try_into().unwrap()) as usize, 12)
+        }
+    };
+    let header = bytes
+        .get(header_start..header_start + header_len)
+        .ok_or(NNError::SerializationError)?;
+    let header = std::str::from_utf8(header).map_err(|_| NNError::SerializationError)?;
+    let data = &bytes[header_start + header_len..];
+
+    let descr = header_value(header, "descr")?.trim_matches(|c| c == '\'' || c == '"');
+    let fortran_order = header_value(header, "fortran_order")?.starts_with("True");
+    let shape = header_value(header, "shape")?
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| d.parse::<usize>().map_err(|_| NNError::SerializationError))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut values: Vec<f32> = match descr {
+        "<f4" => data
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect(),
+        "<f8" => data
+            .chunks_exact(8)
+            .map(|b| f64::from_le_bytes(b.try_into().unwrap()) as f32)
+            .collect(),
+        _ => return Err(NNError::SerializationError),
+    };
+
+    if shape.iter().try_fold(1usize, |n, &d| n.checked_mul(d)) != Some(values.len()) {
+        return Err(NNError::SerializationError);
+    }
+
+    if fortran_order && let [rows, cols] = shape[..] {
+        values = (0..rows)
+            .flat_map(|r| (0..cols).map(move |c| (r, c)))
+            .map(|(r, c)| values[c * rows + r])
+            .collect();
+    }
+
+    Ok((shape, values))
+}
+
+/// Raw text of `key`'s value in the header dict literal.
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, NNError> {
+    let start = header
+        .find(&format!("'{}':", key))
+        .ok_or(NNError::SerializationError)?
+        + key.len()
+        + 3;
+    let rest = header[start..].trim_start();
+
+    let end = if rest.starts_with('(') {
+        rest.find(')').map(|i| i + 1)
+    } else {
+        rest.find([',', '}'])
+    };
+
+    Ok(rest[..end.ok_or(NNError::SerializationError)?].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::ndarray_engine::NdArrayEngine;
+    use crate::port::classifier::ModelStateExporter;
+
+    #[test]
+    fn export_import_round_trip() {
+        let state = NdArrayEngine::with_seed(8).export_state().unwrap();
+
+        let bytes = export_npz(&state).unwrap();
+
+        assert_eq!(import_npz(&bytes).unwrap(), state);
+    }
+
+    #[test]
+    fn npy_header_is_aligned() {
+        let npy = encode_npy(&[10], &[0.0; 10]);
+        let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+
+        assert_eq!((10 + header_len) % 64, 0);
+        assert_eq!(npy[10 + header_len - 1], b'\n');
+        assert!(
+            std::str::from_utf8(&npy[10..10 + header_len])
+                .unwrap()
+                .contains("'shape': (10,)")
+        );
+    }
+
+    #[test]
+    fn rejects_overflowing_shape() {
+        let header =
+            "{'descr': '<f4', 'fortran_order': False, 'shape': (4294967296, 4294967296), }\n";
+
+        let mut npy = NPY_MAGIC.to_vec();
+        npy.extend_from_slice(&[1, 0]);
+        npy.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        npy.extend_from_slice(header.as_bytes());
+
+        assert_eq!(decode_npy(&npy), Err(NNError::SerializationError));
+    }
+
+    #[test]
+    fn decodes_fortran_ordered_f64() {
+        let values: Vec<f64> = vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0]; // 2x3, column-major
+        let header = "{'descr': '<f8', 'fortran_order': True, 'shape': (2, 3), }\n";
+
+        let mut npy = NPY_MAGIC.to_vec();
+        npy.extend_from_slice(&[1, 0]);
+        npy.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        npy.extend_from_slice(header.as_bytes());
+        npy.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+
+        let (shape, data) = decode_npy(&npy).unwrap();
+
+        assert_eq!(shape, vec![2, 3]);
+        assert_eq!(data, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn rejects_missing_tensor() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file("w1.npy", FileOptions::default()).unwrap();
+        writer
+            .write_all(&encode_npy(&[128, 784], &vec![0.0; 128 * 784]))
+            .unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        assert!(import_npz(&bytes).is_err());
+    }
+}
@@ -0,0 +1,549 @@
+//! ONNX export and import for the `Gemm -> Relu -> Gemm -> Softmax` MLP.
+//!
+//! The exported graph takes `input: float[batch, 784]` with pixels already
+//! scaled to `[0, 1]` (i.e. divided by 255) and returns `output: float[batch, 10]`
+//! probabilities. The importer accepts the same subset of operators, plus an
+//! optional leading `Flatten`, and any `Gemm` attribute combination except
+//! `transA`.
+
+use std::collections::HashMap;
+
+use crate::adapter::protobuf::{
+    Reader, Value, decode_packed_varints, encode_bytes, encode_varint_field,
+};
+use crate::domain::{ModelState, error::NNError};
+
+const IR_VERSION: u64 = 8;
+const OPSET_VERSION: u64 = 13;
+const FLOAT: u64 = 1;
+
+const ATTRIBUTE_INT: u64 = 2;
+
+// -------- Export --------
+
+pub fn export_onnx(state: &ModelState) -> Result<Vec<u8>, NNError> {
+    state.check_shapes()?;
+
+    let mut graph = Vec::new();
+
+    let nodes = [
+        node(
+            "gemm_1",
+            "Gemm",
+            &["input", "w1", "b1"],
+            "z1",
+            &[int_attribute("transB", 1)],
+        ),
+        node("relu_1", "Relu", &["z1"], "a1", &[]),
+        node(
+            "gemm_2",
+            "Gemm",
+            &["a1", "w2", "b2"],
+            "z2",
+            &[int_attribute("transB", 1)],
+        ),
+        node(
+            "softmax",
+            "Softmax",
+            &["z2"],
+            "output",
+            &[int_attribute("axis", 1)],
+        ),
+    ];
+    for n in &nodes {
+        encode_bytes(&mut graph, 1, n);
+    }
+
+    encode_bytes(&mut graph, 2, b"mnist_mlp");
+
+    encode_bytes(&mut graph, 5, &tensor("w1", &[128, 784], &state.w1));
+    encode_bytes(&mut graph, 5, &tensor("b1", &[128], &state.b1));
+    encode_bytes(&mut graph, 5, &tensor("w2", &[10, 128], &state.w2));
+    encode_bytes(&mut graph, 5, &tensor("b2", &[10], &state.b2));
+
+    encode_bytes(&mut graph, 11, &value_info("input", 784));
+    encode_bytes(&mut graph, 12, &value_info("output", 10));
+
+    let mut opset = Vec::new();
+    encode_varint_field(&mut opset, 2, OPSET_VERSION);
+
+    let mut model = Vec::new();
+    encode_varint_field(&mut model, 1, IR_VERSION);
+    encode_bytes(&mut model, 2, b"mnist-rs");
+    encode_bytes(&mut model, 3, env!("CARGO_PKG_VERSION").as_bytes());
+    encode_bytes(&mut model, 7, &graph);
+    encode_bytes(&mut model, 8, &opset);
+
+    Ok(model)
+}
+
+fn tensor(name: &str, dims: &[u64], data: &[f32]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for dim in dims {
+        encode_varint_field(&mut buf, 1, *dim);
+    }
+    encode_varint_field(&mut buf, 2, FLOAT);
+    encode_bytes(&mut buf, 8, name.as_bytes());
+
+    let raw: Vec<u8> = data.iter().flat_map(|v| v.to_le_bytes()).collect();
+    encode_bytes(&mut buf, 9, &raw);
+
+    buf
+}
+
+/// `float[batch, features]` value info.
+fn value_info(name: &str, features: u64) -> Vec<u8> {
+    let mut batch = Vec::new();
+    encode_bytes(&mut batch, 2, b"batch");
+    let mut width = Vec::new();
+    encode_varint_field(&mut width, 1, features);
+
+    let mut shape = Vec::new();
+    encode_bytes(&mut shape, 1, &batch);
+    encode_bytes(&mut shape, 1, &width);
+
+    let mut tensor_type = Vec::new();
+    encode_varint_field(&mut tensor_type, 1, FLOAT);
+    encode_bytes(&mut tensor_type, 2, &shape);
+
+    let mut type_proto = Vec::new();
+    encode_bytes(&mut type_proto, 1, &tensor_type);
+
+    let mut buf = Vec::new();
+    encode_bytes(&mut buf, 1, name.as_bytes());
+    encode_bytes(&mut buf, 2, &type_proto);
+    buf
+}
+
+fn node(
+    name: &str,
+    op_type: &str,
+    inputs: &[&str],
+    output: &str,
+    attributes: &[Vec<u8>],
+) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for input in inputs {
+        encode_bytes(&mut buf, 1, input.as_bytes());
+    }
+    encode_bytes(&mut buf, 2, output.as_bytes());
+    encode_bytes(&mut buf, 3, name.as_bytes());
+    encode_bytes(&mut buf, 4, op_type.as_bytes());
+    for attribute in attributes {
+        encode_bytes(&mut buf, 5, attribute);
+    }
+    buf
+}
+
+fn int_attribute(name: &str, value: u64) -> Vec<u8> {
+    let mut buf = Vec::new();
+    encode_bytes(&mut buf, 1, name.as_bytes());
+    encode_varint_field(&mut buf, 3, value);
+    encode_varint_field(&mut buf, 20, ATTRIBUTE_INT);
+    buf
+}
+
+// -------- Import --------
+
+struct Tensor {
+    dims: Vec<usize>,
+    data: Vec<f32>,
+}
+
+#[derive(Default)]
+struct Node {
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    op_type: String,
+    ints: HashMap<String, i64>,
+    floats: HashMap<String, f32>,
+}
+
+enum Layer {
+    Dense { weights: Vec<f32>, bias: Vec<f32> },
+    Relu,
+    Softmax,
+}
+
+pub fn import_onnx(bytes: &[u8]) -> Result<ModelState, NNError> {
+    let mut graph = None;
+    for field in Reader::new(bytes) {
+        if let (7, value) = field? {
+            graph = Some(value.as_bytes()?);
+        }
+    }
+    let graph = graph.ok_or(NNError::SerializationError)?;
+
+    let mut nodes = Vec::new();
+    let mut initializers = HashMap::new();
+    let mut inputs = Vec::new();
+
+    for field in Reader::new(graph) {
+        match field? {
+            (1, value) => nodes.push(parse_node(value.as_bytes()?)?),
+            (5, value) => {
+                let (name, tensor) = parse_tensor(value.as_bytes()?)?;
+                initializers.insert(name, tensor);
+            }
+            (11, value) => inputs.push(parse_value_info_name(value.as_bytes()?)?),
+            _ => {}
+        }
+    }
+
+    // Older exporters also list initializers among the graph inputs.
+    let mut current = inputs
+        .into_iter()
+        .find(|name| !initializers.contains_key(name))
+        .ok_or(NNError::SerializationError)?;
+
+    let mut layers = Vec::new();
+    let mut visited = 0;
+
+    while let Some(node) = nodes.iter().find(|n| n.inputs.first() == Some(&current)) {
+        // A chain can't be longer than the graph; more steps mean a cycle.
+        visited += 1;
+        if visited > nodes.len() {
+            return Err(NNError::SerializationError);
+        }
+
+        match node.op_type.as_str() {
+            "Flatten" if layers.is_empty() => {}
+            "Gemm" => layers.push(dense_layer(node, &initializers)?),
+            "Relu" => layers.push(Layer::Relu),
+            "Softmax" => layers.push(Layer::Softmax),
+            _ => return Err(NNError::SerializationError),
+        }
+
+        current = node
+            .outputs
+            .first()
+            .ok_or(NNError::SerializationError)?
+            .clone();
+    }
+
+    let state = match layers.as_slice() {
+        [
+            Layer::Dense {
+                weights: w1,
+                bias: b1,
+                ..
+            },
+            Layer::Relu,
+            Layer::Dense {
+                weights: w2,
+                bias: b2,
+                ..
+            },
+        ]
+        | [
+            Layer::Dense {
+                weights: w1,
+                bias: b1,
+                ..
+            },
+            Layer::Relu,
+            Layer::Dense {
+                weights: w2,
+                bias: b2,
+                ..
+            },
+            Layer::Softmax,
+        ] => ModelState {
+            w1: w1.clone(),
+            b1: b1.clone(),
+            w2: w2.clone(),
+            b2: b2.clone(),
+        },
+        _ => return Err(NNError::SerializationError),
+    };
+
+    state.check_shapes()?;
+    Ok(state)
+}
+
+fn dense_layer(node: &Node, initializers: &HashMap<String, Tensor>) -> Result<Layer, NNError> {
+    if node.ints.get("transA").copied().unwrap_or(0) != 0 {
+        return Err(NNError::SerializationError);
+    }
+
+    let b = node
+        .inputs
+        .get(1)
+        .and_then(|name| initializers.get(name))
+        .ok_or(NNError::SerializationError)?;
+    if b.dims.len() != 2 {
+        return Err(NNError::SerializationError);
+    }
+
+    let alpha = node.floats.get("alpha").copied().unwrap_or(1.0);
+    let beta = node.floats.get("beta").copied().unwrap_or(1.0);
+
+    // Our layout is [out, in], which is what `transB = 1` expects.
+    let (rows, mut weights) = if node.ints.get("transB").copied().unwrap_or(0) != 0 {
+        (b.dims[0], b.data.clone())
+    } else {
+        let (k, n) = (b.dims[0], b.dims[1]);
+        let transposed = (0..n)
+            .flat_map(|row| (0..k).map(move |col| b.data[col * n + row]))
+            .collect();
+        (n, transposed)
+    };
+    weights.iter_mut().for_each(|w| *w *= alpha);
+
+    let bias = match node.inputs.get(2).filter(|name| !name.is_empty()) {
+        Some(name) => {
+            let c = initializers.get(name).ok_or(NNError::SerializationError)?;
+            if c.data.len() != rows {
+                return Err(NNError::SerializationError);
+            }
+            c.data.iter().map(|v| v * beta).collect()
+        }
+        None => vec![0.0; rows],
+    };
+
+    Ok(Layer::Dense { weights, bias })
+}
+
+fn parse_node(bytes: &[u8]) -> Result<Node, NNError> {
+    let mut node = Node::default();
+
+    for field in Reader::new(bytes) {
+        match field? {
+            (1, value) => node.inputs.push(value.as_str()?.to_string()),
+            (2, value) => node.outputs.push(value.as_str()?.to_string()),
+            (4, value) => node.op_type = value.as_str()?.to_string(),
+            (5, value) => {
+                let mut name = String::new();
+                let mut int = None;
+                let mut float = None;
+
+                for attr in Reader::new(value.as_bytes()?) {
+                    match attr? {
+                        (1, v) => name = v.as_str()?.to_string(),
+                        (2, v) => float = Some(v.as_f32()?),
+                        (3, v) => int = Some(v.as_u64()? as i64),
+                        _ => {}
+                    }
+                }
+
+                if let Some(i) = int {
+                    node.ints.insert(name.clone(), i);
+                }
+                if let Some(f) = float {
+                    node.floats.insert(name, f);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(node)
+}
+
+fn parse_tensor(bytes: &[u8]) -> Result<(String, Tensor), NNError> {
+    let mut name = String::new();
+    let mut dims = Vec::new();
+    let mut data_type = 0;
+    let mut raw = None;
+    let mut floats = Vec::new();
+
+    for field in Reader::new(bytes) {
+        match field? {
+            (1, value) => dims.extend(
+                decode_packed_varints(&value)?
+                    .into_iter()
+                    .map(|d| d as usize),
+            ),
+            (2, value) => data_type = value.as_u64()?,
+            (4, Value::Bytes(packed)) => floats.extend(
+                packed
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes(b.try_into().unwrap())),
+            ),
+            (4, value) => floats.push(value.as_f32()?),
+            (8, value) => name = value.as_str()?.to_string(),
+            (9, value) => raw = Some(value.as_bytes()?),
+            _ => {}
+        }
+    }
+
+    if data_type != FLOAT {
+        return Err(NNError::SerializationError);
+    }
+
+    let data = match raw {
+        Some(raw) => raw
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect(),
+        None => floats,
+    };
+
+    if dims.iter().try_fold(1usize, |n, &d| n.checked_mul(d)) != Some(data.len()) {
+        return Err(NNError::SerializationError);
+    }
+
+    Ok((name, Tensor { dims, data }))
+}
+
+fn parse_value_info_name(bytes: &[u8]) -> Result<String, NNError> {
+    for field in Reader::new(bytes) {
+        if let (1, value) = field? {
+            return Ok(value.as_str()?.to_string());
+        }
+    }
+    Err(NNError::SerializationError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::ndarray_engine::NdArrayEngine;
+    use crate::adapter::protobuf::encode_float;
+    use crate::port::classifier::ModelStateExporter;
+
+    fn float_attribute(name: &str, value: f32) -> Vec<u8> {
+        let mut buf = Vec::new();
+        encode_bytes(&mut buf, 1, name.as_bytes());
+        encode_float(&mut buf, 2, value);
+        encode_varint_field(&mut buf, 20, 1);
+        buf
+    }
+
+    fn model_with(nodes: &[Vec<u8>], initializers: &[Vec<u8>]) -> Vec<u8> {
+        let mut graph = Vec::new();
+        for n in nodes {
+            encode_bytes(&mut graph, 1, n);
+        }
+        for t in initializers {
+            encode_bytes(&mut graph, 5, t);
+        }
+        encode_bytes(&mut graph, 11, &value_info("x", 784));
+
+        let mut model = Vec::new();
+        encode_varint_field(&mut model, 1, IR_VERSION);
+        encode_bytes(&mut model, 7, &graph);
+        model
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let state = NdArrayEngine::with_seed(5).export_state().unwrap();
+
+        let bytes = export_onnx(&state).unwrap();
+        let imported = import_onnx(&bytes).unwrap();
+
+        assert_eq!(state, imported);
+    }
+
+    #[test]
+    fn imports_untransposed_gemm_with_alpha_beta() {
+        let state = NdArrayEngine::with_seed(9).export_state().unwrap();
+
+        // w2 stored as [in, out] with transB = 0 and scaled by alpha/beta.
+        let w2_t: Vec<f32> = (0..128)
+            .flat_map(|i| (0..10).map(move |o| (o, i)))
+            .map(|(o, i)| state.w2[o * 128 + i] / 2.0)
+            .collect();
+        let b2_scaled: Vec<f32> = state.b2.iter().map(|b| b * 4.0).collect();
+
+        let bytes = model_with(
+            &[
+                node("flatten", "Flatten", &["x"], "flat", &[]),
+                node(
+                    "g1",
+                    "Gemm",
+                    &["flat", "w1", "b1"],
+                    "h",
+                    &[int_attribute("transB", 1)],
+                ),
+                node("r", "Relu", &["h"], "a", &[]),
+                node(
+                    "g2",
+                    "Gemm",
+                    &["a", "w2", "b2"],
+                    "logits",
+                    &[float_attribute("alpha", 2.0), float_attribute("beta", 0.25)],
+                ),
+            ],
+            &[
+                tensor("w1", &[128, 784], &state.w1),
+                tensor("b1", &[128], &state.b1),
+                tensor("w2", &[128, 10], &w2_t),
+                tensor("b2", &[10], &b2_scaled),
+            ],
+        );
+
+        let imported = import_onnx(&bytes).unwrap();
+
+        assert_eq!(imported.w1, state.w1);
+        for (a, b) in imported.w2.iter().zip(&state.w2) {
+            assert!((a - b).abs() < 1e-6);
+        }
+        for (a, b) in imported.b2.iter().zip(&state.b2) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_operators() {
+        let state = NdArrayEngine::with_seed(1).export_state().unwrap();
+
+        let bytes = model_with(
+            &[
+                node(
+                    "g1",
+                    "Gemm",
+                    &["x", "w1", "b1"],
+                    "h",
+                    &[int_attribute("transB", 1)],
+                ),
+                node("t", "Tanh", &["h"], "a", &[]),
+                node(
+                    "g2",
+                    "Gemm",
+                    &["a", "w2", "b2"],
+                    "y",
+                    &[int_attribute("transB", 1)],
+                ),
+            ],
+            &[
+                tensor("w1", &[128, 784], &state.w1),
+                tensor("b1", &[128], &state.b1),
+                tensor("w2", &[10, 128], &state.w2),
+                tensor("b2", &[10], &state.b2),
+            ],
+        );
+
+        assert!(import_onnx(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_cyclic_graphs() {
+        for op_type in ["Flatten", "Relu"] {
+            let bytes = model_with(&[node("loop", op_type, &["x"], "x", &[])], &[]);
+            assert!(import_onnx(&bytes).is_err());
+        }
+
+        let bytes = model_with(
+            &[
+                node("a", "Relu", &["x"], "y", &[]),
+                node("b", "Relu", &["y"], "x", &[]),
+            ],
+            &[],
+        );
+        assert!(import_onnx(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_overflowing_dims() {
+        let tensor = tensor("w1", &[1 << 32, 1 << 32], &[]);
+
+        assert!(parse_tensor(&tensor).is_err());
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(import_onnx(&[0xff, 0xff, 0xff]).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::adapter::ndarray_engine::NdArrayEngine;
+use crate::domain::{BatchTrainingResult, TrainingSample, error::NNError};
+
+/// Data-parallel mini-batch trainer for [`NdArrayEngine`].
+///
+/// Each batch is split into one contiguous chunk per thread. Every thread sums
+/// the gradients of its chunk in sample order, and the per-chunk sums are then
+/// reduced in chunk order, so the result only depends on the batch contents
+/// and the thread count.
+pub struct ParallelBatchTrainer {
+    pool: ThreadPool,
+    threads: usize,
+}
+
+impl ParallelBatchTrainer {
+    pub fn new(threads: usize) -> Result<Self, NNError> {
+        let threads = threads.max(1);
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("nn-train-{}", i))
+            .build()
+            .map_err(|_| NNError::InternalError)?;
+
+        Ok(Self { pool, threads })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn train_batch(
+        &self,
+        engine: &mut NdArrayEngine,
+        batch: &[TrainingSample],
+    ) -> Result<BatchTrainingResult, NNError> {
+        if batch.is_empty() {
+            return Err(NNError::InvalidInput);
+        }
+
+        let chunk_size = batch.len().div_ceil(self.threads);
+        let model = &*engine;
+
+        let partials = self.pool.install(|| {
+            batch
+                .par_chunks(chunk_size)
+                .map(|chunk| model.gradients(chunk))
+                .collect::<Result<Vec<_>, NNError>>()
+        })?;
+
+        let mut partials = partials.into_iter();
+        let mut total = partials.next().ok_or(NNError::InternalError)?;
+        for grads in partials {
+            total.accumulate(&grads);
+        }
+
+        engine.apply_gradients(&total);
+
+        Ok(total.result())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::port::classifier::{BatchTrainer, ModelStateExporter};
+
+    fn sample_batch() -> Vec<TrainingSample> {
+        (0..16)
+            .map(|i| TrainingSample {
+                label: (i % 10) as u8,
+                pixels: (0..784).map(|p| ((p * (i + 1)) % 256) as u8).collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_and_threads_give_identical_weights() {
+        let batch = sample_batch();
+        let trainer = ParallelBatchTrainer::new(4).unwrap();
+
+        let mut a = NdArrayEngine::with_seed(42);
+        let mut b = NdArrayEngine::with_seed(42);
+
+        for _ in 0..3 {
+            trainer.train_batch(&mut a, &batch).unwrap();
+            trainer.train_batch(&mut b, &batch).unwrap();
+        }
+
+        assert_eq!(a.export_state().unwrap(), b.export_state().unwrap());
+    }
+
+    #[test]
+    fn single_thread_matches_sequential_batch_training() {
+        let batch = sample_batch();
+        let trainer = ParallelBatchTrainer::new(1).unwrap();
+
+        let mut parallel = NdArrayEngine::with_seed(7);
+        let mut sequential = NdArrayEngine::with_seed(7);
+
+        let r1 = trainer.train_batch(&mut parallel, &batch).unwrap();
+        let r2 = sequential.train_batch(&batch).unwrap();
+
+        assert_eq!(r1.loss, r2.loss);
+        assert_eq!(r1.correct, r2.correct);
+        assert_eq!(
+            parallel.export_state().unwrap(),
+            sequential.export_state().unwrap()
+        );
+    }
+
+    #[test]
+    fn more_threads_than_samples() {
+        let batch = sample_batch()[..3].to_vec();
+        let trainer = ParallelBatchTrainer::new(8).unwrap();
+        let mut engine = NdArrayEngine::with_seed(1);
+
+        let result = trainer.train_batch(&mut engine, &batch).unwrap();
+
+        assert_eq!(result.samples, 3);
+    }
+
+    #[test]
+    fn invalid_sample_fails_whole_batch() {
+        let mut batch = sample_batch();
+        batch[5].pixels.truncate(10);
+        let trainer = ParallelBatchTrainer::new(2).unwrap();
+        let mut engine = NdArrayEngine::with_seed(1);
+        let before = engine.export_state().unwrap();
+
+        assert!(trainer.train_batch(&mut engine, &batch).is_err());
+        assert_eq!(before, engine.export_state().unwrap());
+    }
+}
@@ -0,0 +1,39 @@
+//! PNG encoding for rendered weights and other grayscale images.
+
+use crate::domain::{GrayImage, error::NNError};
+
+pub fn encode_png(image: &GrayImage) -> Result<Vec<u8>, NNError> {
+    let io = |e: png::EncodingError| NNError::IoError(e.to_string());
+    let mut bytes = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut bytes, image.width as u32, image.height as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(io)?;
+    writer.write_image_data(&image.pixels).map_err(io)?;
+    writer.finish().map_err(io)?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_a_decoder() {
+        let image = GrayImage::new(3, 2, vec![0, 64, 128, 192, 255, 7]).unwrap();
+
+        let bytes = encode_png(&image).unwrap();
+        let mut reader = png::Decoder::new(std::io::Cursor::new(bytes))
+            .read_info()
+            .unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        let frame = reader.next_frame(&mut pixels).unwrap();
+
+        assert_eq!((frame.width, frame.height), (3, 2));
+        assert_eq!(frame.color_type, png::ColorType::Grayscale);
+        assert_eq!(&pixels[..frame.buffer_size()], &image.pixels[..]);
+    }
+}
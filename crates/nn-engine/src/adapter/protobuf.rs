@@ -0,0 +1,213 @@
+//! Minimal protobuf wire-format helpers for the hand-encoded formats
+//! (TensorBoard event files, ONNX models).
+
+use crate::domain::error::NNError;
+
+// -------- Encoding --------
+
+pub(crate) fn encode_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn encode_key(buf: &mut Vec<u8>, field: u32, wire_type: u8) {
+    encode_varint(buf, ((field as u64) << 3) | wire_type as u64);
+}
+
+pub(crate) fn encode_varint_field(buf: &mut Vec<u8>, field: u32, value: u64) {
+    encode_key(buf, field, 0);
+    encode_varint(buf, value);
+}
+
+#[cfg_attr(not(feature = "server"), allow(dead_code))]
+pub(crate) fn encode_double(buf: &mut Vec<u8>, field: u32, value: f64) {
+    encode_key(buf, field, 1);
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+pub(crate) fn encode_bytes(buf: &mut Vec<u8>, field: u32, value: &[u8]) {
+    encode_key(buf, field, 2);
+    encode_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+#[cfg_attr(not(feature = "server"), allow(dead_code))]
+pub(crate) fn encode_float(buf: &mut Vec<u8>, field: u32, value: f32) {
+    encode_key(buf, field, 5);
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+// -------- Decoding --------
+
+/// A single decoded field; length-delimited payloads borrow from the input.
+/// 64-bit fixed payloads are skipped since no decoded message uses them.
+pub(crate) enum Value<'a> {
+    Varint(u64),
+    Fixed64,
+    Bytes(&'a [u8]),
+    Fixed32([u8; 4]),
+}
+
+impl<'a> Value<'a> {
+    pub(crate) fn as_u64(&self) -> Result<u64, NNError> {
+        match self {
+            Value::Varint(v) => Ok(*v),
+            _ => Err(NNError::SerializationError),
+        }
+    }
+
+    pub(crate) fn as_bytes(&self) -> Result<&'a [u8], NNError> {
+        match self {
+            Value::Bytes(b) => Ok(b),
+            _ => Err(NNError::SerializationError),
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Result<&'a str, NNError> {
+        std::str::from_utf8(self.as_bytes()?).map_err(|_| NNError::SerializationError)
+    }
+
+    pub(crate) fn as_f32(&self) -> Result<f32, NNError> {
+        match self {
+            Value::Fixed32(b) => Ok(f32::from_le_bytes(*b)),
+            _ => Err(NNError::SerializationError),
+        }
+    }
+}
+
+/// Iterates over the `(field number, value)` pairs of one message.
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn varint(&mut self) -> Result<u64, NNError> {
+        let mut value = 0u64;
+
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self.data.split_first().ok_or(NNError::SerializationError)?;
+            self.data = rest;
+
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(NNError::SerializationError)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], NNError> {
+        if self.data.len() < len {
+            return Err(NNError::SerializationError);
+        }
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn field(&mut self) -> Result<(u32, Value<'a>), NNError> {
+        let key = self.varint()?;
+        let field = (key >> 3) as u32;
+
+        let value = match key & 0x7 {
+            0 => Value::Varint(self.varint()?),
+            1 => {
+                self.take(8)?;
+                Value::Fixed64
+            }
+            2 => {
+                let len = self.varint()? as usize;
+                Value::Bytes(self.take(len)?)
+            }
+            5 => Value::Fixed32(self.take(4)?.try_into().unwrap()),
+            _ => return Err(NNError::SerializationError),
+        };
+
+        Ok((field, value))
+    }
+}
+
+impl<'a> Iterator for Reader<'a> {
+    type Item = Result<(u32, Value<'a>), NNError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let item = self.field();
+        if item.is_err() {
+            self.data = &[];
+        }
+        Some(item)
+    }
+}
+
+/// Decodes a packed `repeated` varint field, also accepting a single
+/// unpacked element.
+pub(crate) fn decode_packed_varints(value: &Value) -> Result<Vec<u64>, NNError> {
+    match value {
+        Value::Varint(v) => Ok(vec![*v]),
+        Value::Bytes(bytes) => {
+            let mut reader = Reader::new(bytes);
+            let mut values = Vec::new();
+            while !reader.data.is_empty() {
+                values.push(reader.varint()?);
+            }
+            Ok(values)
+        }
+        _ => Err(NNError::SerializationError),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_all_wire_types() {
+        let mut buf = Vec::new();
+        encode_varint_field(&mut buf, 1, 300);
+        encode_double(&mut buf, 2, 1.5);
+        encode_bytes(&mut buf, 3, b"abc");
+        encode_float(&mut buf, 4, -2.25);
+
+        let fields: Vec<_> = Reader::new(&buf).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields[0].0, 1);
+        assert_eq!(fields[0].1.as_u64().unwrap(), 300);
+        assert!(matches!(fields[1].1, Value::Fixed64));
+        assert_eq!(fields[2].1.as_str().unwrap(), "abc");
+        assert_eq!(fields[3].1.as_f32().unwrap(), -2.25);
+    }
+
+    #[test]
+    fn truncated_input_is_an_error() {
+        let mut buf = Vec::new();
+        encode_bytes(&mut buf, 1, b"abcdef");
+        buf.truncate(4);
+
+        assert!(Reader::new(&buf).any(|f| f.is_err()));
+    }
+
+    #[test]
+    fn packed_varints() {
+        let mut packed = Vec::new();
+        for v in [3u64, 128, 784] {
+            encode_varint(&mut packed, v);
+        }
+
+        let values = decode_packed_varints(&Value::Bytes(&packed)).unwrap();
+
+        assert_eq!(values, vec![3, 128, 784]);
+    }
+}
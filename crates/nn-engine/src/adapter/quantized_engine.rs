@@ -0,0 +1,337 @@
+use crate::domain::{
+    ModelState, Prediction, QuantizationReport, QuantizationScheme, QuantizedModelState,
+    TrainingSample, error::NNError,
+};
+use crate::port::classifier::DigitPredictor;
+
+/// Share of calibration activations kept inside the hidden-layer range;
+/// the rest are clipped so a few outliers do not waste the uint8 range.
+const CALIBRATION_PERCENTILE: f32 = 0.9999;
+
+/// Inference-only engine running on int8 weights.
+///
+/// Inputs are used as raw uint8 pixels and hidden activations are requantized
+/// to uint8 with the calibrated scale, so both matrix products accumulate in
+/// `i32`; only the per-row rescaling, biases and softmax are done in `f32`.
+pub struct QuantizedEngine {
+    state: QuantizedModelState,
+}
+
+impl QuantizedEngine {
+    pub fn new(state: QuantizedModelState) -> Result<Self, NNError> {
+        let scales = |rows: usize| match state.scheme {
+            QuantizationScheme::PerTensor => 1,
+            QuantizationScheme::PerChannel => rows,
+        };
+
+        let valid = state.w1.len() == 128 * 784
+            && state.b1.len() == 128
+            && state.w2.len() == 10 * 128
+            && state.b2.len() == 10
+            && state.w1_scales.len() == scales(128)
+            && state.w2_scales.len() == scales(10)
+            && state.hidden_scale > 0.0
+            && state.hidden_scale.is_finite()
+            && [&state.w1_scales, &state.b1, &state.w2_scales, &state.b2]
+                .iter()
+                .all(|values| values.iter().all(|v| v.is_finite()));
+
+        if !valid {
+            return Err(NNError::SerializationError);
+        }
+
+        Ok(Self { state })
+    }
+
+    pub fn state(&self) -> &QuantizedModelState {
+        &self.state
+    }
+
+    /// Quantizes float weights, calibrating the hidden activation range on
+    /// `calibration` images (784 raw pixels each).
+    pub fn quantize(
+        state: &ModelState,
+        scheme: QuantizationScheme,
+        calibration: &[Vec<u8>],
+    ) -> Result<QuantizedModelState, NNError> {
+        if state.w1.len() != 128 * 784
+            || state.b1.len() != 128
+            || state.w2.len() != 10 * 128
+            || state.b2.len() != 10
+        {
+            return Err(NNError::SerializationError);
+        }
+
+        let (w1, w1_scales) = quantize_matrix(&state.w1, 128, 784, scheme);
+        let (w2, w2_scales) = quantize_matrix(&state.w2, 10, 128, scheme);
+
+        let hidden_scale = calibrate_hidden(state, calibration)? / 255.0;
+
+        Ok(QuantizedModelState {
+            scheme,
+            w1,
+            w1_scales,
+            b1: state.b1.clone(),
+            w2,
+            w2_scales,
+            b2: state.b2.clone(),
+            hidden_scale: if hidden_scale > 0.0 {
+                hidden_scale
+            } else {
+                1.0 / 255.0
+            },
+        })
+    }
+
+    fn output(&self, pixels: &[u8]) -> Result<Vec<f32>, NNError> {
+        if pixels.len() != 784 {
+            return Err(NNError::InvalidInput);
+        }
+
+        let s = &self.state;
+        let input_scale = 1.0 / 255.0;
+
+        let mut hidden = [0u8; 128];
+        for (row, h) in hidden.iter_mut().enumerate() {
+            let acc = dot_i8_u8(&s.w1[row * 784..(row + 1) * 784], pixels);
+            let z = acc as f32 * scale_of(&s.w1_scales, row) * input_scale + s.b1[row];
+
+            *h = (z.max(0.0) / s.hidden_scale).round().min(255.0) as u8;
+        }
+
+        let mut logits = [0f32; 10];
+        for (row, logit) in logits.iter_mut().enumerate() {
+            let acc = dot_i8_u8(&s.w2[row * 128..(row + 1) * 128], &hidden);
+            *logit = acc as f32 * scale_of(&s.w2_scales, row) * s.hidden_scale + s.b2[row];
+        }
+
+        let max = logits.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let exp: Vec<f32> = logits.iter().map(|v| (v - max).exp()).collect();
+        let sum: f32 = exp.iter().sum();
+
+        Ok(exp.into_iter().map(|v| v / sum).collect())
+    }
+}
+
+impl DigitPredictor for QuantizedEngine {
+    fn predict(&self, pixels: &[u8]) -> Result<Prediction, NNError> {
+        let output = self.output(pixels)?;
+
+        let (digit, confidence) = output
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+
+        Ok(Prediction {
+            digit: digit as u8,
+            confidence: *confidence,
+        })
+    }
+}
+
+/// Evaluates a float and a quantized model on the same labelled samples.
+pub fn quantization_report(
+    float: &(impl DigitPredictor + ?Sized),
+    float_state: &ModelState,
+    quantized: &QuantizedEngine,
+    samples: &[TrainingSample],
+) -> Result<QuantizationReport, NNError> {
+    let mut float_correct = 0usize;
+    let mut quantized_correct = 0usize;
+    let mut agree = 0usize;
+    let mut confidence_delta = 0.0f32;
+
+    for sample in samples {
+        let f = float.predict(&sample.pixels)?;
+        let q = quantized.predict(&sample.pixels)?;
+
+        float_correct += (f.digit == sample.label) as usize;
+        quantized_correct += (q.digit == sample.label) as usize;
+        agree += (f.digit == q.digit) as usize;
+        confidence_delta += (f.confidence - q.confidence).abs();
+    }
+
+    let n = samples.len().max(1) as f32;
+
+    Ok(QuantizationReport {
+        samples: samples.len(),
+        float_accuracy: float_correct as f32 / n,
+        quantized_accuracy: quantized_correct as f32 / n,
+        agreement: agree as f32 / n,
+        mean_confidence_delta: confidence_delta / n,
+        float_size_bytes: float_state.size_bytes(),
+        quantized_size_bytes: quantized.state().size_bytes(),
+    })
+}
+
+fn scale_of(scales: &[f32], row: usize) -> f32 {
+    if scales.len() == 1 {
+        scales[0]
+    } else {
+        scales[row]
+    }
+}
+
+fn dot_i8_u8(weights: &[i8], values: &[u8]) -> i32 {
+    weights
+        .iter()
+        .zip(values)
+        .map(|(w, v)| *w as i32 * *v as i32)
+        .sum()
+}
+
+fn symmetric_scale(values: &[f32]) -> f32 {
+    let max = values.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+    if max > 0.0 { max / 127.0 } else { 1.0 }
+}
+
+pub(crate) fn quantize_matrix(
+    weights: &[f32],
+    rows: usize,
+    cols: usize,
+    scheme: QuantizationScheme,
+) -> (Vec<i8>, Vec<f32>) {
+    let scales: Vec<f32> = match scheme {
+        QuantizationScheme::PerTensor => vec![symmetric_scale(weights)],
+        QuantizationScheme::PerChannel => weights.chunks(cols).map(symmetric_scale).collect(),
+    };
+
+    let quantized = (0..rows)
+        .flat_map(|row| {
+            let scale = scale_of(&scales, row);
+            weights[row * cols..(row + 1) * cols]
+                .iter()
+                .map(move |w| (w / scale).round().clamp(-127.0, 127.0) as i8)
+        })
+        .collect();
+
+    (quantized, scales)
+}
+
+/// Returns the upper bound of the hidden activations over `calibration`.
+fn calibrate_hidden(state: &ModelState, calibration: &[Vec<u8>]) -> Result<f32, NNError> {
+    if calibration.is_empty() {
+        return Err(NNError::InvalidInput);
+    }
+
+    let mut activations = Vec::with_capacity(calibration.len() * 128);
+
+    for pixels in calibration {
+        if pixels.len() != 784 {
+            return Err(NNError::InvalidInput);
+        }
+
+        for row in 0..128 {
+            let z: f32 = state.w1[row * 784..(row + 1) * 784]
+                .iter()
+                .zip(pixels)
+                .map(|(w, p)| w * (*p as f32 / 255.0))
+                .sum::<f32>()
+                + state.b1[row];
+
+            activations.push(z.max(0.0));
+        }
+    }
+
+    activations.sort_by(f32::total_cmp);
+    let index = ((activations.len() - 1) as f32 * CALIBRATION_PERCENTILE).round() as usize;
+
+    Ok(activations[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::ndarray_engine::NdArrayEngine;
+    use crate::port::classifier::{DigitTrainer, ModelStateExporter};
+
+    fn images() -> Vec<Vec<u8>> {
+        (0..20)
+            .map(|i| (0..784).map(|p| ((p * (i + 3)) % 256) as u8).collect())
+            .collect()
+    }
+
+    #[test]
+    fn quantized_predictions_track_float_model() {
+        let mut engine = NdArrayEngine::with_seed(11);
+        let samples: Vec<TrainingSample> = images()
+            .into_iter()
+            .enumerate()
+            .map(|(i, pixels)| TrainingSample {
+                label: (i % 10) as u8,
+                pixels,
+            })
+            .collect();
+
+        for _ in 0..20 {
+            for sample in &samples {
+                engine.train(sample.label, &sample.pixels).unwrap();
+            }
+        }
+
+        let float_state = engine.export_state().unwrap();
+
+        for scheme in [
+            QuantizationScheme::PerTensor,
+            QuantizationScheme::PerChannel,
+        ] {
+            let calibration: Vec<Vec<u8>> = samples.iter().map(|s| s.pixels.clone()).collect();
+            let state = QuantizedEngine::quantize(&float_state, scheme, &calibration).unwrap();
+            let quantized = QuantizedEngine::new(state).unwrap();
+
+            let report = quantization_report(&engine, &float_state, &quantized, &samples).unwrap();
+
+            assert_eq!(report.samples, 20);
+            assert!(report.agreement >= 0.9, "{:?}: {:?}", scheme, report);
+            assert!(report.quantized_size_bytes * 3 < report.float_size_bytes);
+        }
+    }
+
+    #[test]
+    fn per_channel_scales_have_one_entry_per_row() {
+        let state = NdArrayEngine::with_seed(1).export_state().unwrap();
+
+        let q =
+            QuantizedEngine::quantize(&state, QuantizationScheme::PerChannel, &images()).unwrap();
+
+        assert_eq!(q.w1_scales.len(), 128);
+        assert_eq!(q.w2_scales.len(), 10);
+        assert!(q.w1.iter().all(|w| *w >= -127));
+    }
+
+    #[test]
+    fn rejects_inconsistent_state() {
+        let state = NdArrayEngine::with_seed(1).export_state().unwrap();
+        let mut q =
+            QuantizedEngine::quantize(&state, QuantizationScheme::PerTensor, &images()).unwrap();
+        q.w1_scales.push(1.0);
+
+        assert!(QuantizedEngine::new(q).is_err());
+
+        for poison in [f32::NAN, f32::INFINITY] {
+            let mut q = QuantizedEngine::quantize(&state, QuantizationScheme::PerTensor, &images())
+                .unwrap();
+            q.w2_scales[0] = poison;
+            assert!(QuantizedEngine::new(q).is_err());
+
+            let mut q = QuantizedEngine::quantize(&state, QuantizationScheme::PerTensor, &images())
+                .unwrap();
+            q.b2[0] = poison;
+            assert!(QuantizedEngine::new(q).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_input_length() {
+        let state = NdArrayEngine::with_seed(1).export_state().unwrap();
+        let q =
+            QuantizedEngine::quantize(&state, QuantizationScheme::PerTensor, &images()).unwrap();
+        let engine = QuantizedEngine::new(q).unwrap();
+
+        assert!(matches!(
+            engine.predict(&[0u8; 10]),
+            Err(NNError::InvalidInput)
+        ));
+    }
+}
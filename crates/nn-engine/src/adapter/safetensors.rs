@@ -0,0 +1,153 @@
+//! [safetensors](https://huggingface.co/docs/safetensors) encoding of
+//! [`ModelState`]: an 8-byte little-endian header length, a JSON header with
+//! `dtype`, `shape` and `data_offsets` per tensor, then the raw tensor bytes.
+//!
+//! Tensors are written as `F32`; `F64` is also accepted on load since that is
+//! what NumPy produces unless told otherwise.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{ModelState, error::NNError};
+
+#[derive(Serialize, Deserialize)]
+struct TensorInfo {
+    dtype: String,
+    shape: Vec<usize>,
+    data_offsets: [usize; 2],
+}
+
+pub fn export_safetensors(state: &ModelState) -> Result<Vec<u8>, NNError> {
+    state.check_shapes()?;
+
+    let mut header = serde_json::Map::new();
+    let mut data = Vec::with_capacity(state.size_bytes());
+
+    for (name, shape, values) in state.tensors() {
+        let start = data.len();
+        data.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+
+        let info = TensorInfo {
+            dtype: "F32".to_string(),
+            shape: shape.to_vec(),
+            data_offsets: [start, data.len()],
+        };
+        header.insert(
+            name.to_string(),
+            serde_json::to_value(info).map_err(|_| NNError::SerializationError)?,
+        );
+    }
+
+    let mut header = serde_json::to_vec(&header).map_err(|_| NNError::SerializationError)?;
+    // The data section must start 8-byte aligned.
+    header.resize(header.len().next_multiple_of(8), b' ');
+
+    let mut bytes = Vec::with_capacity(8 + header.len() + data.len());
+    bytes.extend_from_slice(&(header.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&header);
+    bytes.extend_from_slice(&data);
+
+    Ok(bytes)
+}
+
+pub fn import_safetensors(bytes: &[u8]) -> Result<ModelState, NNError> {
+    let header_len = bytes
+        .get(..8)
+        .map(|b| u64::from_le_bytes(b.try_into().unwrap()) as usize)
+        .ok_or(NNError::SerializationError)?;
+    let header = bytes
+        .get(8..8usize.saturating_add(header_len))
+        .ok_or(NNError::SerializationError)?;
+    let data = &bytes[8 + header_len..];
+
+    let header: HashMap<String, serde_json::Value> =
+        serde_json::from_slice(header).map_err(|_| NNError::SerializationError)?;
+
+    let mut tensors = HashMap::new();
+
+    for (name, value) in header {
+        if name == "__metadata__" {
+            continue;
+        }
+
+        let info: TensorInfo =
+            serde_json::from_value(value).map_err(|_| NNError::SerializationError)?;
+        let [start, end] = info.data_offsets;
+        let raw = data.get(start..end).ok_or(NNError::SerializationError)?;
+
+        let values = match info.dtype.as_str() {
+            "F32" => raw
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+                .collect(),
+            "F64" => raw
+                .chunks_exact(8)
+                .map(|b| f64::from_le_bytes(b.try_into().unwrap()) as f32)
+                .collect(),
+            _ => return Err(NNError::SerializationError),
+        };
+
+        tensors.insert(name, (info.shape, values));
+    }
+
+    ModelState::from_tensors(tensors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::ndarray_engine::NdArrayEngine;
+    use crate::port::classifier::ModelStateExporter;
+
+    #[test]
+    fn export_import_round_trip() {
+        let state = NdArrayEngine::with_seed(5).export_state().unwrap();
+
+        let bytes = export_safetensors(&state).unwrap();
+        let header_len = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+
+        assert_eq!(header_len % 8, 0);
+        assert_eq!(bytes.len(), 8 + header_len + state.size_bytes());
+        assert_eq!(import_safetensors(&bytes).unwrap(), state);
+    }
+
+    #[test]
+    fn imports_f64_tensors_with_metadata() {
+        let state = NdArrayEngine::with_seed(6).export_state().unwrap();
+
+        let mut header = serde_json::Map::new();
+        header.insert("__metadata__".into(), serde_json::json!({"format": "np"}));
+        let mut data = Vec::new();
+        for (name, shape, values) in state.tensors() {
+            let start = data.len();
+            data.extend(values.iter().flat_map(|v| (*v as f64).to_le_bytes()));
+            header.insert(
+                name.into(),
+                serde_json::json!({"dtype": "F64", "shape": shape, "data_offsets": [start, data.len()]}),
+            );
+        }
+        let header = serde_json::to_vec(&header).unwrap();
+
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend(header);
+        bytes.extend(data);
+
+        assert_eq!(import_safetensors(&bytes).unwrap(), state);
+    }
+
+    #[test]
+    fn rejects_missing_or_misshaped_tensors() {
+        let state = NdArrayEngine::with_seed(7).export_state().unwrap();
+        let mut bytes = export_safetensors(&state).unwrap();
+
+        // Corrupt the "shape" of the first tensor in the header.
+        let header_len = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+        let header = String::from_utf8(bytes[8..8 + header_len].to_vec()).unwrap();
+        let corrupted = header.replacen("[128,784]", "[784,128]", 1);
+        bytes.splice(8..8 + header_len, corrupted.into_bytes());
+
+        assert!(import_safetensors(&bytes).is_err());
+        assert!(import_safetensors(&bytes[..4]).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! User-submitted samples as an append-only JSON lines file, exportable to
+//! the MNIST CSV layout `bin/train.rs` reads, and a reader for that layout.
+
+use async_trait::async_trait;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+use crate::domain::{MNIST_SIDE, SubmittedSample, TrainingSample, error::NNError};
+use crate::port::sample_store::SampleStore;
+
+pub struct JsonLinesSampleStore {
+    path: PathBuf,
+    /// Serialises appends so lines never interleave.
+    writing: Mutex<()>,
+}
+
+impl JsonLinesSampleStore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            writing: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl SampleStore for JsonLinesSampleStore {
+    async fn append(&self, sample: &SubmittedSample) -> Result<(), NNError> {
+        let mut line = serde_json::to_vec(sample).map_err(|_| NNError::SerializationError)?;
+        line.push(b'\n');
+
+        let _guard = self.writing.lock().await;
+        let path = self.path.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            file.write_all(&line)?;
+            file.sync_data()
+        })
+        .await
+        .map_err(|_| NNError::InternalError)?
+        .map_err(|e| NNError::IoError(e.to_string()))
+    }
+
+    async fn load(&self) -> Result<Vec<SubmittedSample>, NNError> {
+        let path = self.path.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let file = match std::fs::File::open(path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(NNError::IoError(e.to_string())),
+            };
+
+            BufReader::new(file)
+                .lines()
+                .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+                .map(|line| {
+                    let line = line.map_err(|e| NNError::IoError(e.to_string()))?;
+                    serde_json::from_str(&line).map_err(|_| NNError::SerializationError)
+                })
+                .collect()
+        })
+        .await
+        .map_err(|_| NNError::InternalError)?
+    }
+}
+
+/// Writes `label,1x1,…,28x28` rows, the layout of `mnist_train.csv`.
+pub fn export_training_csv(samples: &[SubmittedSample], writer: impl Write) -> Result<(), NNError> {
+    let mut csv = csv::Writer::from_writer(writer);
+    let io = |e: csv::Error| NNError::IoError(e.to_string());
+
+    let header = std::iter::once("label".to_string()).chain(
+        (1..=MNIST_SIDE).flat_map(|row| (1..=MNIST_SIDE).map(move |col| format!("{row}x{col}"))),
+    );
+    csv.write_record(header).map_err(io)?;
+
+    for sample in samples {
+        if sample.pixels.len() != MNIST_SIDE * MNIST_SIDE {
+            return Err(NNError::InvalidInput);
+        }
+        let row = std::iter::once(sample.label.to_string())
+            .chain(sample.pixels.iter().map(|p| p.to_string()));
+        csv.write_record(row).map_err(io)?;
+    }
+
+    csv.flush().map_err(|e| NNError::IoError(e.to_string()))
+}
+
+/// Reads up to `limit` rows of a `label,1x1,…,28x28` CSV such as
+/// `mnist_train.csv`.
+pub fn read_training_csv(reader: impl Read, limit: usize) -> Result<Vec<TrainingSample>, NNError> {
+    let mut csv = csv::Reader::from_reader(reader);
+
+    csv.records()
+        .take(limit)
+        .map(|record| {
+            let record = record.map_err(|e| NNError::IoError(e.to_string()))?;
+            let mut fields = record.iter().map(str::parse::<u8>);
+
+            let label = fields
+                .next()
+                .and_then(Result::ok)
+                .filter(|&label| label <= 9)
+                .ok_or(NNError::SerializationError)?;
+            let pixels = fields
+                .collect::<Result<Vec<u8>, _>>()
+                .map_err(|_| NNError::SerializationError)?;
+            if pixels.len() != MNIST_SIDE * MNIST_SIDE {
+                return Err(NNError::SerializationError);
+            }
+
+            Ok(TrainingSample { label, pixels })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Prediction;
+
+    fn sample(label: u8) -> SubmittedSample {
+        SubmittedSample {
+            label,
+            pixels: (0..784).map(|i| (i % 256) as u8).collect(),
+            timestamp: 1.5e9,
+            model_version: "abc+0".to_string(),
+            prediction: Prediction {
+                digit: 1,
+                confidence: 0.5,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn appends_and_exports_train_csv() {
+        let path = std::env::temp_dir().join(format!(
+            "mnist_sample_store_test_{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let store = JsonLinesSampleStore::new(&path);
+
+        assert!(store.load().await.unwrap().is_empty());
+        store.append(&sample(4)).await.unwrap();
+        store.append(&sample(9)).await.unwrap();
+
+        let samples = JsonLinesSampleStore::new(&path).load().await.unwrap();
+        assert_eq!(samples, vec![sample(4), sample(9)]);
+
+        let mut csv = Vec::new();
+        export_training_csv(&samples, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("label,1x1,1x2,"));
+        assert!(lines[0].ends_with(",28x28"));
+        assert_eq!(lines[1].split(',').count(), 785);
+        assert!(lines[2].starts_with("9,0,1,2,"));
+
+        let read = read_training_csv(csv.as_bytes(), 1).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].label, 4);
+        assert_eq!(read[0].pixels, sample(4).pixels);
+        assert!(read_training_csv("label,1x1\n3,0\n".as_bytes(), 10).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
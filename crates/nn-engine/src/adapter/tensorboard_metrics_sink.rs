@@ -0,0 +1,166 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::adapter::metrics_sink::unix_time;
+use crate::adapter::protobuf::{encode_bytes, encode_double, encode_float, encode_varint_field};
+use crate::domain::{TrainingMetrics, error::NNError};
+use crate::port::metrics_sink::MetricsSink;
+
+/// Writes TensorBoard `events.out.tfevents.*` files.
+///
+/// Events are framed as TFRecords and hand-encoded as the small subset of
+/// the `Event`/`Summary` protobuf messages needed for scalar summaries.
+pub struct TensorBoardMetricsSink {
+    writer: BufWriter<File>,
+}
+
+impl TensorBoardMetricsSink {
+    /// Creates a new event file inside `log_dir`, creating the directory if needed.
+    pub fn create(log_dir: impl AsRef<Path>) -> Result<Self, NNError> {
+        let log_dir = log_dir.as_ref();
+        fs::create_dir_all(log_dir).map_err(|e| NNError::IoError(e.to_string()))?;
+
+        let wall_time = unix_time();
+        let path = log_dir.join(format!("events.out.tfevents.{}.mnist-rs", wall_time as u64));
+        let file = File::create(path).map_err(|e| NNError::IoError(e.to_string()))?;
+
+        let mut sink = Self {
+            writer: BufWriter::new(file),
+        };
+
+        let mut event = Vec::new();
+        encode_double(&mut event, 1, wall_time);
+        encode_bytes(&mut event, 3, b"brain.Event:2");
+        sink.write_record(&event)?;
+
+        Ok(sink)
+    }
+
+    fn write_record(&mut self, data: &[u8]) -> Result<(), NNError> {
+        let len = (data.len() as u64).to_le_bytes();
+
+        let mut frame = Vec::with_capacity(data.len() + 16);
+        frame.extend_from_slice(&len);
+        frame.extend_from_slice(&masked_crc32c(&len).to_le_bytes());
+        frame.extend_from_slice(data);
+        frame.extend_from_slice(&masked_crc32c(data).to_le_bytes());
+
+        self.writer
+            .write_all(&frame)
+            .map_err(|e| NNError::IoError(e.to_string()))
+    }
+}
+
+impl MetricsSink for TensorBoardMetricsSink {
+    fn record(&mut self, metrics: &TrainingMetrics) -> Result<(), NNError> {
+        let scope = metrics.scope.as_str();
+
+        let mut summary = Vec::new();
+        for (name, value) in [("loss", metrics.loss), ("accuracy", metrics.accuracy)] {
+            let mut entry = Vec::new();
+            encode_bytes(&mut entry, 1, format!("{}/{}", scope, name).as_bytes());
+            encode_float(&mut entry, 2, value);
+            encode_bytes(&mut summary, 1, &entry);
+        }
+
+        let mut event = Vec::new();
+        encode_double(&mut event, 1, unix_time());
+        encode_varint_field(&mut event, 2, metrics.step as u64);
+        encode_bytes(&mut event, 5, &summary);
+
+        self.write_record(&event)
+    }
+
+    fn flush(&mut self) -> Result<(), NNError> {
+        self.writer
+            .flush()
+            .map_err(|e| NNError::IoError(e.to_string()))
+    }
+}
+
+// -------- TFRecord checksums --------
+
+fn crc32c(data: &[u8]) -> u32 {
+    const POLY: u32 = 0x82f6_3b78;
+
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (POLY & mask);
+        }
+    }
+    !crc
+}
+
+fn masked_crc32c(data: &[u8]) -> u32 {
+    let crc = crc32c(data);
+    crc.rotate_right(15).wrapping_add(0xa282_ead8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MetricsScope;
+    use std::env;
+
+    #[test]
+    fn crc32c_matches_reference_value() {
+        assert_eq!(crc32c(b"123456789"), 0xe306_9283);
+    }
+
+    #[test]
+    fn writes_framed_records() {
+        let mut dir = env::temp_dir();
+        dir.push(format!("tensorboard_metrics_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut sink = TensorBoardMetricsSink::create(&dir).unwrap();
+        sink.record(&TrainingMetrics {
+            scope: MetricsScope::Step,
+            epoch: 1,
+            step: 5000,
+            samples: 5000,
+            loss: 0.5,
+            accuracy: 0.75,
+        })
+        .unwrap();
+        sink.flush().unwrap();
+
+        let entry = fs::read_dir(&dir).unwrap().next().unwrap().unwrap();
+        assert!(
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("events.out.tfevents.")
+        );
+
+        let bytes = fs::read(entry.path()).unwrap();
+        let mut offset = 0;
+        let mut records = 0;
+
+        while offset < bytes.len() {
+            let len_bytes = &bytes[offset..offset + 8];
+            let len = u64::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+            let len_crc = u32::from_le_bytes(bytes[offset + 8..offset + 12].try_into().unwrap());
+            assert_eq!(len_crc, masked_crc32c(len_bytes));
+
+            let data = &bytes[offset + 12..offset + 12 + len];
+            let data_crc = u32::from_le_bytes(
+                bytes[offset + 12 + len..offset + 16 + len]
+                    .try_into()
+                    .unwrap(),
+            );
+            assert_eq!(data_crc, masked_crc32c(data));
+
+            offset += 16 + len;
+            records += 1;
+        }
+
+        assert_eq!(records, 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,37 +1,127 @@
 use async_trait::async_trait;
-use std::sync::Arc;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
 
-use crate::adapter::ndarray_engine::NdArrayEngine;
-use crate::adapter::async_ndarray_engine::AsyncNdArrayEngine;
+use crate::adapter::async_ndarray_engine::{AsyncNdArrayEngine, LockWaitMetrics};
 use crate::adapter::file_repository::FileModelRepository;
-use crate::domain::Prediction;
-use crate::domain::TrainingStepResult;
+use crate::adapter::metrics_sink::unix_time;
+use crate::adapter::ndarray_engine::NdArrayEngine;
 use crate::domain::error::NNError;
+use crate::domain::{
+    Activations, Attribution, AttributionMethod, ReplayBuffer, TrainingSample, TrainingStepResult,
+};
+use crate::domain::{ModelInfo, ModelState, Prediction};
 use crate::port::async_classifier::{
+    AsyncActivationExtractor, AsyncAttributionExplainer, AsyncBatchPredictor, AsyncBatchTrainer,
     AsyncDigitPredictor, AsyncDigitTrainer, AsyncModelStateExporter, AsyncModelStateImporter,
 };
+use crate::port::classifier::BatchPredictor;
 use crate::port::model_repository::ModelRepository;
 
+/// The model currently served and how it has changed since it was loaded.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelStatus {
+    #[serde(flatten)]
+    pub info: ModelInfo,
+    /// Checksum of the weights as loaded; `checksum` follows online training.
+    pub loaded_checksum: String,
+    /// Unix time in seconds.
+    pub loaded_at: f64,
+    pub training_steps: u64,
+}
+
+struct LoadedModel {
+    checksum: String,
+    at: f64,
+}
+
 pub struct DigitClassifierService {
     engine: AsyncNdArrayEngine,
     repo: Arc<dyn ModelRepository + Send + Sync>,
+    loaded: RwLock<Option<LoadedModel>>,
+    training_steps: AtomicU64,
+    replay: Option<Arc<ReplayBuffer>>,
 }
 
 impl DigitClassifierService {
     pub fn new(engine: AsyncNdArrayEngine, repo: Arc<dyn ModelRepository + Send + Sync>) -> Self {
-        Self { engine, repo }
+        Self {
+            engine,
+            repo,
+            loaded: RwLock::new(None),
+            training_steps: AtomicU64::new(0),
+            replay: None,
+        }
+    }
+
+    /// Trains online samples together with rehearsal samples from `replay`,
+    /// discarding updates its validation guard rejects.
+    pub fn with_replay(mut self, replay: ReplayBuffer) -> Self {
+        self.replay = Some(Arc::new(replay));
+        self
     }
 
     pub async fn load_model(&self) -> Result<(), NNError> {
         let state = self.repo.load().await?;
+        state.check_shapes()?;
+        state.check_finite()?;
+        let checksum = state.checksum();
         self.engine.import_state(state).await?;
+
+        *self.loaded.write().map_err(|_| NNError::InternalError)? = Some(LoadedModel {
+            checksum,
+            at: unix_time(),
+        });
+        self.training_steps.store(0, Ordering::Relaxed);
+
         Ok(())
     }
 
+    /// Whether a model has been loaded from the repository.
+    pub fn is_loaded(&self) -> bool {
+        self.loaded.read().is_ok_and(|loaded| loaded.is_some())
+    }
+
+    /// Short id of the served weights: the loaded checksum prefix and the
+    /// number of online training steps since, e.g. `3f2a9c01d4e7+12`.
+    pub fn version(&self) -> Option<String> {
+        let loaded = self.loaded.read().ok()?;
+        let loaded = loaded.as_ref()?;
+
+        Some(format!(
+            "{}+{}",
+            &loaded.checksum[..12],
+            self.training_steps.load(Ordering::Relaxed)
+        ))
+    }
+
+    /// `None` until [`load_model`](Self::load_model) succeeds.
+    pub async fn status(&self) -> Result<Option<ModelStatus>, NNError> {
+        let (loaded_checksum, loaded_at) =
+            match &*self.loaded.read().map_err(|_| NNError::InternalError)? {
+                Some(loaded) => (loaded.checksum.clone(), loaded.at),
+                None => return Ok(None),
+            };
+
+        let info = self.engine.export_state().await?.info();
+
+        Ok(Some(ModelStatus {
+            info,
+            loaded_checksum,
+            loaded_at,
+            training_steps: self.training_steps.load(Ordering::Relaxed),
+        }))
+    }
+
     pub async fn save_model(&self) -> Result<(), NNError> {
         let state = self.engine.export_state().await?;
         self.repo.save(&state).await
     }
+
+    pub fn lock_wait(&self) -> Arc<LockWaitMetrics> {
+        self.engine.lock_wait()
+    }
 }
 
 impl DigitClassifierService {
@@ -39,7 +129,7 @@ impl DigitClassifierService {
         let engine = AsyncNdArrayEngine::new(NdArrayEngine::new());
         let repo = Arc::new(FileModelRepository::new(path));
 
-        Self { engine, repo }
+        Self::new(engine, repo)
     }
 }
 
@@ -50,9 +140,236 @@ impl AsyncDigitPredictor for DigitClassifierService {
     }
 }
 
+#[async_trait]
+impl AsyncActivationExtractor for DigitClassifierService {
+    async fn activations(&self, pixels: &[u8]) -> Result<Activations, NNError> {
+        self.engine.activations(pixels).await
+    }
+}
+
+#[async_trait]
+impl AsyncAttributionExplainer for DigitClassifierService {
+    async fn explain(
+        &self,
+        pixels: &[u8],
+        class: u8,
+        method: AttributionMethod,
+    ) -> Result<Attribution, NNError> {
+        self.engine.explain(pixels, class, method).await
+    }
+}
+
+#[async_trait]
+impl AsyncBatchPredictor for DigitClassifierService {
+    async fn predict_batch(
+        &self,
+        batch: Vec<Vec<u8>>,
+    ) -> Result<Vec<Result<Prediction, NNError>>, NNError> {
+        self.engine.predict_batch(batch).await
+    }
+}
+
 #[async_trait]
 impl AsyncDigitTrainer for DigitClassifierService {
     async fn train(&self, label: u8, pixels: &[u8]) -> Result<TrainingStepResult, NNError> {
-        self.engine.train(label, pixels).await
+        let step = match &self.replay {
+            Some(replay) => self.train_with_replay(replay, label, pixels).await?,
+            None => self.engine.train(label, pixels).await?,
+        };
+        self.training_steps.fetch_add(1, Ordering::Relaxed);
+        Ok(step)
+    }
+}
+
+impl DigitClassifierService {
+    /// One step on `(label, pixels)` mixed with rehearsal samples. `loss` is
+    /// the mean over the mixed batch; `correct` is about the new sample,
+    /// before the update, as with plain training.
+    async fn train_with_replay(
+        &self,
+        replay: &Arc<ReplayBuffer>,
+        label: u8,
+        pixels: &[u8],
+    ) -> Result<TrainingStepResult, NNError> {
+        if label > 9 {
+            return Err(NNError::InvalidInput);
+        }
+
+        let correct = self.engine.predict(pixels).await?.digit == label;
+        let batch = replay.mix(
+            TrainingSample {
+                label,
+                pixels: pixels.to_vec(),
+            },
+            &mut rand::thread_rng(),
+        );
+
+        let result = match replay.min_accuracy() {
+            None => self.engine.train_batch(batch).await?,
+            Some(threshold) => {
+                let replay = replay.clone();
+                self.engine
+                    .train_batch_if(batch, move |engine| {
+                        let validation: Vec<Vec<u8>> = replay
+                            .validation()
+                            .iter()
+                            .map(|sample| sample.pixels.clone())
+                            .collect();
+                        let accuracy = replay.accuracy(&engine.predict_batch(&validation));
+                        if accuracy < threshold {
+                            return Err(NNError::UpdateRejected {
+                                accuracy,
+                                threshold,
+                            });
+                        }
+
+                        Ok(())
+                    })
+                    .await?
+            }
+        };
+
+        Ok(TrainingStepResult {
+            loss: result.loss,
+            correct,
+        })
+    }
+}
+
+#[async_trait]
+impl AsyncModelStateExporter for DigitClassifierService {
+    async fn export_state(&self) -> Result<ModelState, NNError> {
+        self.engine.export_state().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::file_repository::JsonModelRepository;
+    use crate::port::classifier::ModelStateExporter;
+
+    #[tokio::test]
+    async fn status_tracks_load_and_training() {
+        let path =
+            std::env::temp_dir().join(format!("mnist_service_status_{}.json", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let repo = Arc::new(JsonModelRepository::new(path.clone()));
+
+        let service = DigitClassifierService::new(
+            AsyncNdArrayEngine::new(NdArrayEngine::with_seed(1)),
+            repo.clone(),
+        );
+        assert!(!service.is_loaded());
+        assert!(service.status().await.unwrap().is_none());
+
+        let state = NdArrayEngine::with_seed(2).export_state().unwrap();
+        repo.save(&state).await.unwrap();
+        service.load_model().await.unwrap();
+        service.train(3, &[255u8; 784]).await.unwrap();
+
+        let status = service.status().await.unwrap().unwrap();
+        assert!(service.is_loaded());
+        assert_eq!(status.loaded_checksum, state.checksum());
+        assert_ne!(status.info.checksum, status.loaded_checksum);
+        assert_eq!(status.training_steps, 1);
+        assert!(status.loaded_at > 0.0);
+        assert_eq!(
+            service.version().unwrap(),
+            format!("{}+1", &state.checksum()[..12])
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn samples(seed: u8) -> Vec<TrainingSample> {
+        (0..10)
+            .map(|label| TrainingSample {
+                label,
+                pixels: (0..784)
+                    .map(|i| ((i * (label as usize + 1) + seed as usize * 31) % 256) as u8)
+                    .collect(),
+            })
+            .collect()
+    }
+
+    fn service_with(replay: ReplayBuffer) -> DigitClassifierService {
+        DigitClassifierService::new(
+            AsyncNdArrayEngine::new(NdArrayEngine::with_seed(1)),
+            Arc::new(JsonModelRepository::new("/nonexistent/model.json")),
+        )
+        .with_replay(replay)
+    }
+
+    #[tokio::test]
+    async fn replay_trains_the_sample_with_rehearsal_samples() {
+        let service =
+            service_with(ReplayBuffer::new(samples(1), 4).with_validation(samples(2), 0.0));
+        let before = service.export_state().await.unwrap().checksum();
+
+        let step = service.train(3, &[255u8; 784]).await.unwrap();
+
+        assert!(step.loss.is_finite());
+        assert_ne!(service.export_state().await.unwrap().checksum(), before);
+        assert!(service.train(10, &[0u8; 784]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn updates_below_the_validation_threshold_are_discarded() {
+        // An untrained model can't classify every validation sample.
+        let service =
+            service_with(ReplayBuffer::new(samples(1), 4).with_validation(samples(2), 1.0));
+        let before = service.export_state().await.unwrap().checksum();
+
+        let error = service.train(3, &[255u8; 784]).await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            NNError::UpdateRejected { accuracy, threshold } if accuracy < 1.0 && threshold == 1.0
+        ));
+        assert_eq!(service.export_state().await.unwrap().checksum(), before);
+        assert_eq!(service.training_steps.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn malformed_model_keeps_the_served_one() {
+        let path = std::env::temp_dir().join(format!(
+            "mnist_service_malformed_{}.json",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap().to_string();
+        let repo = Arc::new(JsonModelRepository::new(path.clone()));
+        let service = DigitClassifierService::new(
+            AsyncNdArrayEngine::new(NdArrayEngine::with_seed(1)),
+            repo.clone(),
+        );
+
+        let state = NdArrayEngine::with_seed(2).export_state().unwrap();
+        repo.save(&state).await.unwrap();
+        service.load_model().await.unwrap();
+
+        let mut malformed = state.clone();
+        malformed.b1.truncate(1);
+        repo.save(&malformed).await.unwrap();
+
+        assert_eq!(service.load_model().await, Err(NNError::SerializationError));
+        assert_eq!(
+            service.export_state().await.unwrap().checksum(),
+            state.checksum()
+        );
+        assert_eq!(
+            service.version().unwrap(),
+            format!("{}+0", &state.checksum()[..12])
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn missing_model_leaves_service_unloaded() {
+        let service = DigitClassifierService::from_path("/nonexistent/model.bin");
+
+        assert!(service.load_model().await.is_err());
+        assert!(!service.is_loaded());
     }
 }
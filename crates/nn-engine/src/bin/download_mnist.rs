@@ -59,7 +59,7 @@ async fn download_and_extract(
     let zip_file = File::open(&temp_zip_path)?;
     let mut archive = zip::ZipArchive::new(zip_file)?;
 
-    if archive.len() == 0 {
+    if archive.is_empty() {
         return Err("ZIP archive is empty".into());
     }
 
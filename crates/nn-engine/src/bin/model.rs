@@ -0,0 +1,284 @@
+use std::env;
+use std::process::ExitCode;
+
+use nn_engine::{
+    Activations, CompactDType, CompactModelRepository, FileModelRepository, JsonModelRepository,
+    ModelState, NdArrayEngine, NpzModelRepository, OnnxModelRepository, SafetensorsModelRepository,
+    WeightGridOptions, encode_png,
+    port::{
+        classifier::{ActivationExtractor, DigitPredictor, ModelStateImporter},
+        model_repository::ModelRepository,
+    },
+    read_training_csv, upscale, weight_grid, weight_tiles,
+};
+
+const USAGE: &str = "\
+Usage: model <command>
+
+Formats are picked by extension: .bin (bincode), .json, .safetensors, .npz, .onnx,
+.weights (compact binary, see --dtype)
+
+  convert --from <path> --to <path>   convert a checkpoint between formats
+          [--dtype f32|f16|int8]      element type of a .weights output (default f32)
+  inspect <path>                      architecture, parameter counts, weight stats, checksum
+  diff <a> <b>                        per-tensor differences between two checkpoints
+  verify <path>                       load the checkpoint and run a smoke prediction
+  weights <path> --out <grid.png>     render each hidden unit's w1 row as a 28x28 tile
+          [--columns N] [--scale N]   tiles per row (default 16), pixel upscale (default 1)
+          [--shared-scale]            one gray scale for all tiles instead of one per tile
+          [--tiles <dir>]             also write <dir>/unit_000.png ... one per hidden unit
+  activations <path> <mnist.csv>      hidden-layer activations for one dataset row
+          [--row N]                   row to use (default 0)";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match run(&args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let arg_value = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .cloned()
+    };
+
+    match args.first().map(String::as_str) {
+        Some("convert") => {
+            let from = arg_value("--from").ok_or("convert needs --from <path>")?;
+            let to = arg_value("--to").ok_or("convert needs --to <path>")?;
+            let dtype = match arg_value("--dtype").as_deref() {
+                None | Some("f32") => CompactDType::F32,
+                Some("f16") => CompactDType::F16,
+                Some("int8") => CompactDType::Int8,
+                Some(other) => return Err(format!("Unknown dtype: {}", other).into()),
+            };
+
+            let state = load(&from).await?;
+            repository(&to, dtype)?.save(&state).await?;
+
+            println!("✅ {} → {}", from, to);
+        }
+        Some("inspect") => {
+            let path = args.get(1).ok_or(USAGE)?;
+            inspect(&load(path).await?);
+        }
+        Some("diff") => {
+            let (a, b) = match args {
+                [_, a, b, ..] => (a, b),
+                _ => return Err(USAGE.into()),
+            };
+            diff(&load(a).await?, &load(b).await?);
+        }
+        Some("verify") => {
+            let path = args.get(1).ok_or(USAGE)?;
+            verify(load(path).await?)?;
+            println!("✅ {} is a valid model", path);
+        }
+        Some("weights") => {
+            let path = args.get(1).ok_or(USAGE)?;
+            let out = arg_value("--out").ok_or("weights needs --out <grid.png>")?;
+            let defaults = WeightGridOptions::default();
+            let options = WeightGridOptions {
+                columns: arg_value("--columns").map_or(Ok(defaults.columns), |v| v.parse())?,
+                scale: arg_value("--scale").map_or(Ok(defaults.scale), |v| v.parse())?,
+                shared_scale: args.iter().any(|a| a == "--shared-scale"),
+                ..defaults
+            };
+
+            let state = load(path).await?;
+            std::fs::write(&out, encode_png(&weight_grid(&state, &options)?)?)?;
+            println!("✅ {} → {}", path, out);
+
+            if let Some(dir) = arg_value("--tiles") {
+                std::fs::create_dir_all(&dir)?;
+                let tiles = weight_tiles(&state, options.shared_scale)?;
+                for (unit, tile) in tiles.iter().enumerate() {
+                    let tile = upscale(tile, options.scale);
+                    std::fs::write(format!("{}/unit_{:03}.png", dir, unit), encode_png(&tile)?)?;
+                }
+                println!("✅ {} tiles → {}/", tiles.len(), dir);
+            }
+        }
+        Some("activations") => {
+            let (path, csv) = match args {
+                [_, path, csv, ..] => (path, csv),
+                _ => return Err(USAGE.into()),
+            };
+            let row: usize = arg_value("--row").map_or(Ok(0), |v| v.parse())?;
+
+            let sample = read_training_csv(std::fs::File::open(csv)?, row + 1)?
+                .into_iter()
+                .nth(row)
+                .ok_or_else(|| format!("{} has no row {}", csv, row))?;
+            let mut engine = NdArrayEngine::new();
+            engine.import_state(load(path).await?)?;
+
+            activations(sample.label, &engine.activations(&sample.pixels)?);
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
+
+fn activations(label: u8, activations: &Activations) {
+    let active = activations.hidden.iter().filter(|&&a| a > 0.0).count();
+
+    println!("🏷️  Label: {}", label);
+    println!(
+        "🧠 Hidden: {}/{} units active",
+        active,
+        activations.hidden.len()
+    );
+    println!();
+    println!("{:<6} {:>10}", "unit", "activation");
+    for (unit, value) in activations.strongest().iter().take(10) {
+        println!("{:<6} {:>10.4}", unit, value);
+    }
+    println!();
+    println!("{:<6} {:>10} {:>12}", "digit", "logit", "probability");
+    for (digit, (logit, p)) in activations
+        .logits
+        .iter()
+        .zip(&activations.probabilities)
+        .enumerate()
+    {
+        println!("{:<6} {:>10.4} {:>12.4}", digit, logit, p);
+    }
+}
+
+fn repository(path: &str, dtype: CompactDType) -> Result<Box<dyn ModelRepository>, String> {
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, ext)| ext)
+        .unwrap_or_default();
+
+    Ok(match extension {
+        "bin" => Box::new(FileModelRepository::new(path)),
+        "json" => Box::new(JsonModelRepository::new(path)),
+        "safetensors" => Box::new(SafetensorsModelRepository::new(path)),
+        "npz" => Box::new(NpzModelRepository::new(path)),
+        "onnx" => Box::new(OnnxModelRepository::new(path)),
+        "weights" => Box::new(CompactModelRepository::new(path, dtype)),
+        _ => return Err(format!("Unknown model format: {}", path)),
+    })
+}
+
+async fn load(path: &str) -> Result<ModelState, String> {
+    let state = repository(path, CompactDType::F32)?
+        .load()
+        .await
+        .map_err(|e| format!("Failed to load {}: {}", path, e))?;
+
+    state
+        .check_shapes()
+        .map_err(|_| format!("{} has unexpected tensor shapes", path))?;
+
+    Ok(state)
+}
+
+fn inspect(state: &ModelState) {
+    let info = state.info();
+
+    println!("🏗️  Architecture: {}", info.architecture);
+    println!("🔢 Parameters:   {}", info.parameters);
+    println!("💾 Size:         {} KB", info.size_bytes / 1024);
+    println!("🔐 SHA-256:      {}", info.checksum);
+    println!();
+    println!(
+        "{:<4} {:>10} {:>8} {:>10} {:>10} {:>10} {:>10} {:>7}",
+        "name", "shape", "params", "min", "max", "mean", "std", "zeros"
+    );
+
+    for t in &info.tensors {
+        let shape: Vec<String> = t.shape.iter().map(|d| d.to_string()).collect();
+        println!(
+            "{:<4} {:>10} {:>8} {:>10.4} {:>10.4} {:>10.4} {:>10.4} {:>7}",
+            t.name,
+            shape.join("x"),
+            t.parameters,
+            t.min,
+            t.max,
+            t.mean,
+            t.std,
+            t.zeros
+        );
+    }
+}
+
+fn diff(a: &ModelState, b: &ModelState) {
+    if a.checksum() == b.checksum() {
+        println!("✅ Checkpoints are identical");
+        return;
+    }
+
+    println!(
+        "{:<4} {:>10} {:>12} {:>12} {:>12}",
+        "name", "changed", "max |Δ|", "mean |Δ|", "rms Δ"
+    );
+
+    for ((name, _, x), (_, _, y)) in a.tensors().iter().zip(b.tensors().iter()) {
+        let deltas: Vec<f32> = x.iter().zip(y.iter()).map(|(x, y)| (x - y).abs()).collect();
+        let n = deltas.len().max(1) as f32;
+
+        println!(
+            "{:<4} {:>10} {:>12.6} {:>12.6} {:>12.6}",
+            name,
+            deltas.iter().filter(|d| **d > 0.0).count(),
+            deltas.iter().cloned().fold(0.0f32, f32::max),
+            deltas.iter().sum::<f32>() / n,
+            (deltas.iter().map(|d| d * d).sum::<f32>() / n).sqrt()
+        );
+    }
+}
+
+fn verify(state: ModelState) -> Result<(), Box<dyn std::error::Error>> {
+    if state
+        .tensors()
+        .iter()
+        .any(|(_, _, v)| v.iter().any(|x| !x.is_finite()))
+    {
+        return Err("Model contains NaN or infinite weights".into());
+    }
+
+    let mut engine = NdArrayEngine::new();
+    engine.import_state(state)?;
+
+    // A blank canvas and a crude vertical stroke, roughly a "1".
+    let blank = vec![0u8; 784];
+    let stroke: Vec<u8> = (0..784)
+        .map(|i| {
+            if (13..15).contains(&(i % 28)) && (4..24).contains(&(i / 28)) {
+                255
+            } else {
+                0
+            }
+        })
+        .collect();
+
+    for (name, pixels) in [("blank", blank), ("stroke", stroke)] {
+        let prediction = engine.predict(&pixels)?;
+
+        if !prediction.confidence.is_finite() || !(0.0..=1.0).contains(&prediction.confidence) {
+            return Err(format!("Smoke prediction on {} image is not a probability", name).into());
+        }
+
+        println!(
+            "🔎 {:<6} → {} ({:.1}%)",
+            name,
+            prediction.digit,
+            100.0 * prediction.confidence
+        );
+    }
+
+    Ok(())
+}
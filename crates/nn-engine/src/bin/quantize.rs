@@ -0,0 +1,115 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::BufReader;
+
+use csv::ReaderBuilder;
+
+use nn_engine::{
+    FileModelRepository,
+    NdArrayEngine,
+    QuantizationScheme,
+    QuantizedEngine,
+    TrainingSample,
+    quantization_report,
+    port::{
+        classifier::ModelStateImporter,
+        model_repository::ModelRepository,
+    }
+};
+
+const TRAIN_PATH: &str = "assets/mnist/mnist_train.csv";
+const TEST_PATH: &str = "assets/mnist/mnist_test.csv";
+const MODELS_DIR: &str = "assets/models";
+const CALIBRATION_SAMPLES: usize = 1000;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // -------- CLI args --------
+    let args: Vec<String> = env::args().collect();
+
+    let arg_value = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .cloned()
+    };
+
+    let version = arg_value("--version").unwrap_or_else(|| "default".to_string());
+    let scheme = match arg_value("--scheme").as_deref() {
+        None | Some("per-channel") => QuantizationScheme::PerChannel,
+        Some("per-tensor") => QuantizationScheme::PerTensor,
+        Some(other) => return Err(format!("Unknown scheme: {}", other).into()),
+    };
+    let calibration_samples: usize = arg_value("--calibration")
+        .map(|v| v.parse())
+        .transpose()?
+        .unwrap_or(CALIBRATION_SAMPLES);
+
+    let model_path = format!("{}/{}.bin", MODELS_DIR, version);
+    let output_path = format!("{}/{}.q8.bin", MODELS_DIR, version);
+
+    println!("📂 Loading {}", model_path);
+    let state = FileModelRepository::new(&model_path).load().await?;
+
+    // -------- Calibration --------
+    println!("🎯 Calibrating on {} training samples ({:?})", calibration_samples, scheme);
+    let calibration: Vec<Vec<u8>> = read_samples(TRAIN_PATH, Some(calibration_samples))?
+        .into_iter()
+        .map(|s| s.pixels)
+        .collect();
+
+    let quantized_state = QuantizedEngine::quantize(&state, scheme, &calibration)?;
+    fs::write(&output_path, bincode::serialize(&quantized_state)?)?;
+
+    // -------- Report --------
+    println!("🧪 Evaluating on {}", TEST_PATH);
+    let test_samples = read_samples(TEST_PATH, None)?;
+
+    let mut float_engine = NdArrayEngine::new();
+    float_engine.import_state(state.clone())?;
+    let quantized = QuantizedEngine::new(quantized_state)?;
+
+    let report = quantization_report(&float_engine, &state, &quantized, &test_samples)?;
+
+    println!("\n📊 Quantization report ({} samples)", report.samples);
+    println!("   float accuracy:     {:.2}%", 100.0 * report.float_accuracy);
+    println!("   int8 accuracy:      {:.2}%", 100.0 * report.quantized_accuracy);
+    println!("   agreement:          {:.2}%", 100.0 * report.agreement);
+    println!("   confidence delta:   {:.4}", report.mean_confidence_delta);
+    println!(
+        "   size:               {} KB → {} KB",
+        report.float_size_bytes / 1024,
+        report.quantized_size_bytes / 1024
+    );
+
+    println!("\n✅ Quantized model saved to {}", output_path);
+
+    Ok(())
+}
+
+fn read_samples(
+    path: &str,
+    limit: Option<usize>,
+) -> Result<Vec<TrainingSample>, Box<dyn std::error::Error>> {
+    let file = File::open(path)?;
+    let mut csv_reader = ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(BufReader::new(file));
+
+    let mut samples = Vec::new();
+
+    for result in csv_reader.records().take(limit.unwrap_or(usize::MAX)) {
+        let record = result?;
+
+        let label: u8 = record[0].parse()?;
+        let pixels = record
+            .iter()
+            .skip(1)
+            .map(|v| v.parse::<u8>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        samples.push(TrainingSample { label, pixels });
+    }
+
+    Ok(samples)
+}
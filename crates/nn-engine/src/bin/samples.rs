@@ -0,0 +1,63 @@
+use std::env;
+use std::fs::File;
+use std::io::BufWriter;
+use std::process::ExitCode;
+
+use nn_engine::{JsonLinesSampleStore, export_training_csv, port::sample_store::SampleStore};
+
+const USAGE: &str = "\
+Usage: samples <command>
+
+  export <samples.jsonl> <out.csv>   write stored samples in the layout of mnist_train.csv
+  stats <samples.jsonl>              sample counts per label and model version";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match run(&args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match args {
+        [command, from, to, ..] if command == "export" => {
+            let samples = JsonLinesSampleStore::new(from).load().await?;
+            export_training_csv(&samples, BufWriter::new(File::create(to)?))?;
+            println!("✅ {} samples → {}", samples.len(), to);
+        }
+        [command, path, ..] if command == "stats" => {
+            let samples = JsonLinesSampleStore::new(path).load().await?;
+            let mut labels = [0usize; 10];
+            let mut versions = std::collections::BTreeMap::<&str, usize>::new();
+            for sample in &samples {
+                if let Some(count) = labels.get_mut(sample.label as usize) {
+                    *count += 1;
+                }
+                *versions.entry(sample.model_version.as_str()).or_default() += 1;
+            }
+
+            println!("{} samples", samples.len());
+            for (digit, count) in labels.iter().enumerate() {
+                println!("  {}: {}", digit, count);
+            }
+            println!("By model version:");
+            for (version, count) in versions {
+                let version = if version.is_empty() {
+                    "(none)"
+                } else {
+                    version
+                };
+                println!("  {}: {}", version, count);
+            }
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
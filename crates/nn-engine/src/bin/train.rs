@@ -3,18 +3,25 @@ use std::fs::{self, File};
 use std::io::BufReader;
 
 use csv::ReaderBuilder;
-use tokio;
 
 use nn_engine::{
     FileModelRepository,
     NdArrayEngine,
     AsyncNdArrayEngine,
+    ConsoleMetricsSink,
+    CsvMetricsSink,
+    JsonLinesMetricsSink,
+    TensorBoardMetricsSink,
+    MetricsScope,
+    TrainingMetrics,
+    TrainingSample,
     port::{
         model_repository::ModelRepository,
+        metrics_sink::MetricsSink,
         async_classifier::{
             AsyncModelStateExporter,
             AsyncModelStateImporter,
-            AsyncDigitTrainer,
+            AsyncBatchTrainer,
         }
     }
 };
@@ -22,6 +29,7 @@ use nn_engine::{
 const TRAIN_PATH: &str = "assets/mnist/mnist_train.csv";
 const MODELS_DIR: &str = "assets/models";
 const EPOCHS: usize = 3;
+const LOG_EVERY: usize = 5000;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -37,13 +45,51 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let resume = args.contains(&"--resume".to_string());
 
+    let arg_value = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .cloned()
+    };
+
+    let batch_size: usize = arg_value("--batch-size").map(|v| v.parse()).transpose()?.unwrap_or(1);
+    let threads: usize = arg_value("--threads").map(|v| v.parse()).transpose()?.unwrap_or(1);
+    let seed: Option<u64> = arg_value("--seed").map(|v| v.parse()).transpose()?;
+    let lr: Option<f32> = arg_value("--lr").map(|v| v.parse()).transpose()?;
+
+    // -------- Metrics sinks --------
+    let mut sinks: Vec<Box<dyn MetricsSink>> = vec![Box::new(ConsoleMetricsSink)];
+
+    if let Some(path) = arg_value("--metrics-jsonl") {
+        sinks.push(Box::new(JsonLinesMetricsSink::create(path)?));
+    }
+    if let Some(path) = arg_value("--metrics-csv") {
+        sinks.push(Box::new(CsvMetricsSink::create(path)?));
+    }
+    if let Some(dir) = arg_value("--tensorboard") {
+        sinks.push(Box::new(TensorBoardMetricsSink::create(dir)?));
+    }
+
     fs::create_dir_all(MODELS_DIR)?;
 
     let model_path = format!("{}/{}.bin", MODELS_DIR, version);
 
     println!("🚀 Training version: {}", version);
+    println!("   batch size: {} | threads: {}", batch_size.max(1), threads.max(1));
+
+    let mut model = match seed {
+        Some(seed) => NdArrayEngine::with_seed(seed),
+        None => NdArrayEngine::new(),
+    };
+    if let Some(lr) = lr {
+        model = model.with_learning_rate(lr);
+    }
 
-    let engine = AsyncNdArrayEngine::new(NdArrayEngine::new());
+    let engine = if threads > 1 {
+        AsyncNdArrayEngine::with_threads(model, threads)?
+    } else {
+        AsyncNdArrayEngine::new(model)
+    };
     let repo = FileModelRepository::new(&model_path);
 
     // -------- Resume --------
@@ -55,10 +101,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // -------- Training --------
-    for epoch in 0..EPOCHS {
-        println!("\n📚 Epoch {}/{}", epoch + 1, EPOCHS);
-        train_epoch(&engine).await?;
+    let mut step = 0usize;
+    for epoch in 1..=EPOCHS {
+        println!("\n📚 Epoch {}/{}", epoch, EPOCHS);
+        step = train_epoch(&engine, &mut sinks, batch_size.max(1), epoch, step).await?;
     }
+    sinks.flush()?;
 
     // -------- Save --------
     println!("\n💾 Saving model...");
@@ -70,7 +118,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-async fn train_epoch(engine: &AsyncNdArrayEngine) -> Result<(), Box<dyn std::error::Error>> {
+async fn train_epoch(
+    engine: &AsyncNdArrayEngine,
+    sinks: &mut dyn MetricsSink,
+    batch_size: usize,
+    epoch: usize,
+    mut step: usize,
+) -> Result<usize, Box<dyn std::error::Error>> {
     let file = File::open(TRAIN_PATH)?;
     let reader = BufReader::new(file);
 
@@ -79,8 +133,10 @@ async fn train_epoch(engine: &AsyncNdArrayEngine) -> Result<(), Box<dyn std::err
     let mut total_loss = 0.0;
     let mut total_correct = 0usize;
     let mut count = 0usize;
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut records = csv_reader.records().peekable();
 
-    for result in csv_reader.records() {
+    while let Some(result) = records.next() {
         let record = result?;
 
         let label: u8 = record[0].parse()?;
@@ -91,30 +147,41 @@ async fn train_epoch(engine: &AsyncNdArrayEngine) -> Result<(), Box<dyn std::err
             .map(|v| v.parse::<u8>().unwrap())
             .collect();
 
-        let train_metrics = engine.train(label, &pixels).await?;
+        batch.push(TrainingSample { label, pixels });
 
-        total_loss += train_metrics.loss;
-        if train_metrics.correct {
-            total_correct += 1;
+        if batch.len() < batch_size && records.peek().is_some() {
+            continue;
         }
 
-        count += 1;
+        let train_metrics = engine.train_batch(std::mem::take(&mut batch)).await?;
+
+        total_loss += train_metrics.loss * train_metrics.samples as f32;
+        total_correct += train_metrics.correct;
 
-        if count % 5000 == 0 {
-            println!(
-                "Samples: {} | Avg Loss: {:.4} | Accuracy: {:.2}%",
-                count,
-                total_loss / count as f32,
-                100.0 * total_correct as f32 / count as f32
-            );
+        let logged = count / LOG_EVERY;
+        count += train_metrics.samples;
+        step += train_metrics.samples;
+
+        if count / LOG_EVERY > logged {
+            sinks.record(&TrainingMetrics {
+                scope: MetricsScope::Step,
+                epoch,
+                step,
+                samples: count,
+                loss: total_loss / count as f32,
+                accuracy: total_correct as f32 / count as f32,
+            })?;
         }
     }
 
-    println!(
-        "\n📊 Epoch Result → Loss: {:.4} | Accuracy: {:.2}%",
-        total_loss / count as f32,
-        100.0 * total_correct as f32 / count as f32
-    );
+    sinks.record(&TrainingMetrics {
+        scope: MetricsScope::Epoch,
+        epoch,
+        step,
+        samples: count,
+        loss: total_loss / count as f32,
+        accuracy: total_correct as f32 / count as f32,
+    })?;
 
-    Ok(())
+    Ok(step)
 }
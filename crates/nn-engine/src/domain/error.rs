@@ -1,12 +1,18 @@
 use std::fmt;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum NNError {
     InvalidInput,
     IoError(String),
     SerializationError,
     PersistenceError,
     InternalError,
+    /// An online update was rolled back for leaving validation accuracy below
+    /// the threshold.
+    UpdateRejected {
+        accuracy: f32,
+        threshold: f32,
+    },
 }
 
 impl fmt::Display for NNError {
@@ -17,6 +23,14 @@ impl fmt::Display for NNError {
             NNError::SerializationError => write!(f, "Serialization error"),
             NNError::PersistenceError => write!(f, "Persistence error"),
             NNError::InternalError => write!(f, "InternalError error"),
+            NNError::UpdateRejected {
+                accuracy,
+                threshold,
+            } => write!(
+                f,
+                "Update rejected: validation accuracy {:.3} would fall below {:.3}",
+                accuracy, threshold
+            ),
         }
     }
 }
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+/// How to attribute a class score to the input pixels. Scores are the class
+/// logit, so attributions don't saturate with the softmax.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum AttributionMethod {
+    /// Magnitude of the score's gradient with respect to each pixel.
+    #[default]
+    Saliency,
+    /// Gradients averaged along the path from a black image to the input,
+    /// times the input; they sum to the score difference between the two.
+    IntegratedGradients {
+        #[serde(default = "default_steps")]
+        steps: usize,
+    },
+    /// Score drop when a `patch`×`patch` square is blacked out, averaged over
+    /// the patches covering each pixel.
+    Occlusion {
+        #[serde(default = "default_patch")]
+        patch: usize,
+        #[serde(default = "default_stride")]
+        stride: usize,
+    },
+}
+
+fn default_steps() -> usize {
+    32
+}
+
+fn default_patch() -> usize {
+    4
+}
+
+fn default_stride() -> usize {
+    2
+}
+
+/// A 28×28 attribution map, row-major like the input pixels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attribution {
+    pub method: AttributionMethod,
+    pub class: u8,
+    /// Positive values support `class`, negative ones speak against it.
+    pub values: Vec<f32>,
+}
+
+impl Attribution {
+    /// Values scaled into `[-1, 1]` by the largest magnitude, ready to be
+    /// drawn as a heatmap.
+    pub fn normalized(&self) -> Vec<f32> {
+        let max = self.values.iter().fold(0.0f32, |max, v| max.max(v.abs()));
+        if max == 0.0 {
+            return vec![0.0; self.values.len()];
+        }
+
+        self.values.iter().map(|v| v / max).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn methods_parse_with_defaults() {
+        let method: AttributionMethod = serde_json::from_str(r#"{"name":"occlusion"}"#).unwrap();
+        assert_eq!(
+            method,
+            AttributionMethod::Occlusion {
+                patch: 4,
+                stride: 2
+            }
+        );
+
+        let method: AttributionMethod =
+            serde_json::from_str(r#"{"name":"integrated_gradients","steps":8}"#).unwrap();
+        assert_eq!(method, AttributionMethod::IntegratedGradients { steps: 8 });
+    }
+
+    #[test]
+    fn normalized_keeps_signs_within_unit_range() {
+        let attribution = Attribution {
+            method: AttributionMethod::Saliency,
+            class: 0,
+            values: vec![-4.0, 2.0, 0.0],
+        };
+
+        assert_eq!(attribution.normalized(), vec![-1.0, 0.5, 0.0]);
+    }
+}
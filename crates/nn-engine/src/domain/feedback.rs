@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Prediction, TrainingSample};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedbackStatus {
+    /// Waiting for an admin; never trained on.
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// A user-submitted `(image, label)` pair held for review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Feedback {
+    /// Assigned by the queue on submission.
+    pub id: u64,
+    /// Unix time in seconds, assigned by the queue on submission.
+    pub submitted_at: f64,
+    pub label: u8,
+    pub pixels: Vec<u8>,
+    /// What the model predicted when the sample arrived.
+    pub prediction: Prediction,
+    /// The model confidently disagreed with the label.
+    pub flagged: bool,
+    pub status: FeedbackStatus,
+}
+
+impl Feedback {
+    /// A pending sample, flagged when the model predicted another digit with
+    /// at least `flag_confidence`.
+    pub fn new(label: u8, pixels: Vec<u8>, prediction: Prediction, flag_confidence: f32) -> Self {
+        Self {
+            id: 0,
+            submitted_at: 0.0,
+            flagged: prediction.digit != label && prediction.confidence >= flag_confidence,
+            label,
+            pixels,
+            prediction,
+            status: FeedbackStatus::Pending,
+        }
+    }
+
+    pub fn to_sample(&self) -> TrainingSample {
+        TrainingSample {
+            label: self.label,
+            pixels: self.pixels.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_only_confident_disagreement() {
+        let prediction = |digit, confidence| Prediction { digit, confidence };
+
+        assert!(Feedback::new(1, vec![], prediction(7, 0.95), 0.9).flagged);
+        assert!(!Feedback::new(1, vec![], prediction(7, 0.5), 0.9).flagged);
+        assert!(!Feedback::new(7, vec![], prediction(7, 0.99), 0.9).flagged);
+    }
+}
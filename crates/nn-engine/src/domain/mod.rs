@@ -3,8 +3,38 @@ pub use prediction::Prediction;
 
 pub mod error;
 
+mod explain;
+pub use explain::{Attribution, AttributionMethod};
+
+mod feedback;
+pub use feedback::{Feedback, FeedbackStatus};
+
+mod model_info;
+pub use model_info::{ModelInfo, TensorStats};
+
 mod model_state;
-pub use model_state::ModelState;
+pub use model_state::{MODEL_TENSORS, ModelState};
+
+mod preprocessing;
+pub use preprocessing::{GrayImage, MAX_IMAGE_SIDE, MNIST_SIDE, PreprocessOptions, preprocess};
+
+mod segmentation;
+pub use segmentation::{
+    BoundingBox, DigitSegment, MAX_SEGMENTS, Recognition, RecognizedDigit, recognize,
+    segment_digits,
+};
+
+mod replay;
+pub use replay::ReplayBuffer;
+
+mod quantized_model_state;
+pub use quantized_model_state::{QuantizationReport, QuantizationScheme, QuantizedModelState};
+
+mod visualize;
+pub use visualize::{Activations, WeightGridOptions, upscale, weight_grid, weight_tiles};
 
 pub mod train;
-pub use train::TrainingStepResult;
+pub use train::{
+    BatchTrainingResult, MetricsScope, SubmittedSample, TrainingMetrics, TrainingSample,
+    TrainingStepResult,
+};
@@ -0,0 +1,44 @@
+use serde::Serialize;
+
+/// Summary statistics of one named tensor.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct TensorStats {
+    pub name: String,
+    pub shape: Vec<usize>,
+    pub parameters: usize,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub std: f32,
+    pub zeros: usize,
+}
+
+/// What a checkpoint contains, as reported by `model inspect`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ModelInfo {
+    pub architecture: String,
+    pub parameters: usize,
+    pub size_bytes: usize,
+    /// Hex SHA-256 of the little-endian `f32` tensors in file order.
+    pub checksum: String,
+    pub tensors: Vec<TensorStats>,
+}
+
+impl TensorStats {
+    pub fn new(name: &str, shape: &[usize], values: &[f32]) -> Self {
+        let n = values.len().max(1) as f32;
+        let mean = values.iter().sum::<f32>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n;
+
+        Self {
+            name: name.to_string(),
+            shape: shape.to_vec(),
+            parameters: values.len(),
+            min: values.iter().cloned().fold(f32::INFINITY, f32::min),
+            max: values.iter().cloned().fold(f32::NEG_INFINITY, f32::max),
+            mean,
+            std: variance.sqrt(),
+            zeros: values.iter().filter(|v| **v == 0.0).count(),
+        }
+    }
+}
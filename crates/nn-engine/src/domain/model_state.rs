@@ -1,9 +1,136 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::domain::error::NNError;
+use crate::domain::model_info::{ModelInfo, TensorStats};
+
+/// Named tensors of the model with their row-major shapes, in file order.
+pub const MODEL_TENSORS: [(&str, &[usize]); 4] = [
+    ("w1", &[128, 784]),
+    ("b1", &[128]),
+    ("w2", &[10, 128]),
+    ("b2", &[10]),
+];
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct ModelState {
     pub w1: Vec<f32>, // 128 * 784
     pub b1: Vec<f32>, // 128
     pub w2: Vec<f32>, // 10 * 128
     pub b2: Vec<f32>, // 10
 }
+
+impl ModelState {
+    /// Size of the raw `f32` tensors, ignoring container overhead.
+    pub fn size_bytes(&self) -> usize {
+        4 * (self.w1.len() + self.b1.len() + self.w2.len() + self.b2.len())
+    }
+
+    /// `(name, shape, data)` for every tensor, following [`MODEL_TENSORS`].
+    pub fn tensors(&self) -> [(&'static str, &'static [usize], &[f32]); 4] {
+        let [
+            (w1, w1_shape),
+            (b1, b1_shape),
+            (w2, w2_shape),
+            (b2, b2_shape),
+        ] = MODEL_TENSORS;
+
+        [
+            (w1, w1_shape, &self.w1),
+            (b1, b1_shape, &self.b1),
+            (w2, w2_shape, &self.w2),
+            (b2, b2_shape, &self.b2),
+        ]
+    }
+
+    /// Builds a state from named row-major tensors, checking every shape.
+    /// Unknown names are ignored.
+    pub fn from_tensors(
+        mut tensors: HashMap<String, (Vec<usize>, Vec<f32>)>,
+    ) -> Result<Self, NNError> {
+        let mut take = |name: &str, shape: &[usize]| match tensors.remove(name) {
+            Some((dims, data))
+                if dims == shape && data.len() == shape.iter().product::<usize>() =>
+            {
+                Ok(data)
+            }
+            _ => Err(NNError::SerializationError),
+        };
+
+        let [
+            (w1, w1_shape),
+            (b1, b1_shape),
+            (w2, w2_shape),
+            (b2, b2_shape),
+        ] = MODEL_TENSORS;
+
+        Ok(Self {
+            w1: take(w1, w1_shape)?,
+            b1: take(b1, b1_shape)?,
+            w2: take(w2, w2_shape)?,
+            b2: take(b2, b2_shape)?,
+        })
+    }
+
+    /// Hex SHA-256 over the little-endian tensors, independent of the
+    /// container format the state was loaded from.
+    pub fn checksum(&self) -> String {
+        let mut hasher = Sha256::new();
+        for (_, _, values) in self.tensors() {
+            for v in values {
+                hasher.update(v.to_le_bytes());
+            }
+        }
+
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    pub fn info(&self) -> ModelInfo {
+        let tensors: Vec<TensorStats> = self
+            .tensors()
+            .iter()
+            .map(|(name, shape, values)| TensorStats::new(name, shape, values))
+            .collect();
+
+        ModelInfo {
+            architecture: "784 → 128 (ReLU) → 10 (softmax)".to_string(),
+            parameters: tensors.iter().map(|t| t.parameters).sum(),
+            size_bytes: self.size_bytes(),
+            checksum: self.checksum(),
+            tensors,
+        }
+    }
+
+    pub fn check_shapes(&self) -> Result<(), NNError> {
+        let valid = self
+            .tensors()
+            .iter()
+            .all(|(_, shape, data)| data.len() == shape.iter().product::<usize>());
+
+        if valid {
+            Ok(())
+        } else {
+            Err(NNError::SerializationError)
+        }
+    }
+
+    /// Rejects NaN and infinite weights, which would poison every prediction.
+    pub fn check_finite(&self) -> Result<(), NNError> {
+        let finite = self
+            .tensors()
+            .iter()
+            .all(|(_, _, data)| data.iter().all(|v| v.is_finite()));
+
+        if finite {
+            Ok(())
+        } else {
+            Err(NNError::SerializationError)
+        }
+    }
+}
@@ -0,0 +1,396 @@
+//! MNIST-style normalization of free-form drawings and photos.
+//!
+//! MNIST digits are white on black, cropped to their bounding box, scaled so
+//! the longer side is 20 pixels (keeping the aspect ratio) and placed in a
+//! 28×28 frame with their centre of mass in the middle. [`preprocess`]
+//! reproduces that, so canvas drawings and photo crops look like the
+//! training data.
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::NNError;
+
+pub const MNIST_SIDE: usize = 28;
+/// Larger images are rejected rather than processed.
+pub const MAX_IMAGE_SIDE: usize = 4096;
+const BOX_SIDE: usize = 20;
+/// Pixels at or below this level (after contrast normalization) are
+/// treated as background when looking for the digit's bounding box.
+pub(super) const INK_THRESHOLD: f32 = 32.0;
+
+/// 8-bit grayscale image, row-major.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrayImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl GrayImage {
+    pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Result<Self, NNError> {
+        if width == 0
+            || height == 0
+            || width > MAX_IMAGE_SIDE
+            || height > MAX_IMAGE_SIDE
+            || width.checked_mul(height) != Some(pixels.len())
+        {
+            return Err(NNError::InvalidInput);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreprocessOptions {
+    /// Straighten slanted digits using their second-order moments.
+    pub deskew: bool,
+    /// Invert images whose border is light, i.e. dark ink on paper.
+    pub detect_inversion: bool,
+}
+
+impl Default for PreprocessOptions {
+    fn default() -> Self {
+        Self {
+            deskew: false,
+            detect_inversion: true,
+        }
+    }
+}
+
+/// Returns 784 MNIST-like pixels; an image without any ink yields a blank
+/// frame.
+pub fn preprocess(image: &GrayImage, options: &PreprocessOptions) -> Vec<u8> {
+    let pixels = normalize(image, options.detect_inversion);
+
+    frame_digit(&pixels, image.width, options.deskew)
+}
+
+/// White-on-black `f32` pixels with the background at 0 and the brightest
+/// ink at 255.
+pub(super) fn normalize(image: &GrayImage, detect_inversion: bool) -> Vec<f32> {
+    let (width, height) = (image.width, image.height);
+    let mut pixels: Vec<f32> = image.pixels.iter().map(|p| *p as f32).collect();
+
+    if detect_inversion && border_mean(&pixels, width, height) > 127.0 {
+        pixels.iter_mut().for_each(|p| *p = 255.0 - *p);
+    }
+    normalize_contrast(&mut pixels, width, height);
+
+    pixels
+}
+
+/// Crops normalized pixels to the ink, scales into the 20×20 box and
+/// centres by centre of mass in a 28×28 frame.
+pub(super) fn frame_digit(pixels: &[f32], width: usize, deskew_frame: bool) -> Vec<u8> {
+    let Some((x0, y0, x1, y1)) = bounding_box(pixels, width) else {
+        return vec![0; MNIST_SIDE * MNIST_SIDE];
+    };
+
+    let (crop_w, crop_h) = (x1 - x0 + 1, y1 - y0 + 1);
+    let cropped: Vec<f32> = (y0..=y1)
+        .flat_map(|y| pixels[y * width + x0..=y * width + x1].iter().copied())
+        .collect();
+
+    let scale = BOX_SIDE as f32 / crop_w.max(crop_h) as f32;
+    let box_w = ((crop_w as f32 * scale).round() as usize).clamp(1, BOX_SIDE);
+    let box_h = ((crop_h as f32 * scale).round() as usize).clamp(1, BOX_SIDE);
+    let resized = resize_area(&cropped, crop_w, crop_h, box_w, box_h);
+
+    let mut frame = vec![0.0; MNIST_SIDE * MNIST_SIDE];
+    let (cx, cy) = centre_of_mass(&resized, box_w, box_h);
+    paste(&mut frame, &resized, box_w, box_h, offset(cx), offset(cy));
+
+    if deskew_frame {
+        frame = deskew(&frame);
+
+        let (cx, cy) = centre_of_mass(&frame, MNIST_SIDE, MNIST_SIDE);
+        let shifted = frame.clone();
+        frame.fill(0.0);
+        paste(
+            &mut frame,
+            &shifted,
+            MNIST_SIDE,
+            MNIST_SIDE,
+            offset(cx),
+            offset(cy),
+        );
+    }
+
+    frame
+        .into_iter()
+        .map(|v| v.round().clamp(0.0, 255.0) as u8)
+        .collect()
+}
+
+fn border_mean(pixels: &[f32], width: usize, height: usize) -> f32 {
+    let border: Vec<f32> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|(x, y)| *x == 0 || *y == 0 || *x == width - 1 || *y == height - 1)
+        .map(|(x, y)| pixels[y * width + x])
+        .collect();
+
+    border.iter().sum::<f32>() / border.len() as f32
+}
+
+/// Maps the background level to 0 and the brightest ink to 255, so grey
+/// paper and faint strokes in photos behave like a clean canvas.
+fn normalize_contrast(pixels: &mut [f32], width: usize, height: usize) {
+    let background = border_mean(pixels, width, height);
+    let max = pixels.iter().cloned().fold(0.0, f32::max);
+
+    if max - background < INK_THRESHOLD {
+        pixels.fill(0.0);
+        return;
+    }
+
+    let scale = 255.0 / (max - background);
+    pixels
+        .iter_mut()
+        .for_each(|p| *p = ((*p - background) * scale).max(0.0));
+}
+
+fn bounding_box(pixels: &[f32], width: usize) -> Option<(usize, usize, usize, usize)> {
+    let mut bounds: Option<(usize, usize, usize, usize)> = None;
+
+    for (i, _) in pixels
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| **p > INK_THRESHOLD)
+    {
+        let (x, y) = (i % width, i / width);
+        bounds = Some(match bounds {
+            None => (x, y, x, y),
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+        });
+    }
+
+    bounds
+}
+
+/// Area-averaging resize: every output pixel is the mean of the source
+/// area it covers, which keeps thin strokes when shrinking large canvases.
+fn resize_area(src: &[f32], src_w: usize, src_h: usize, dst_w: usize, dst_h: usize) -> Vec<f32> {
+    let horizontal = axis_weights(src_w, dst_w);
+    let vertical = axis_weights(src_h, dst_h);
+
+    let mut rows = vec![0.0; dst_w * src_h];
+    for y in 0..src_h {
+        for (x, weights) in horizontal.iter().enumerate() {
+            rows[y * dst_w + x] = weights.iter().map(|(i, w)| src[y * src_w + i] * w).sum();
+        }
+    }
+
+    let mut out = vec![0.0; dst_w * dst_h];
+    for (y, weights) in vertical.iter().enumerate() {
+        for x in 0..dst_w {
+            out[y * dst_w + x] = weights.iter().map(|(i, w)| rows[i * dst_w + x] * w).sum();
+        }
+    }
+
+    out
+}
+
+/// For each output cell, the source cells it overlaps and their share.
+fn axis_weights(src: usize, dst: usize) -> Vec<Vec<(usize, f32)>> {
+    let ratio = src as f32 / dst as f32;
+
+    (0..dst)
+        .map(|o| {
+            let (start, end) = (o as f32 * ratio, (o + 1) as f32 * ratio);
+
+            (start.floor() as usize..(end.ceil() as usize).min(src))
+                .map(|i| {
+                    let overlap = end.min(i as f32 + 1.0) - start.max(i as f32);
+                    (i, overlap / ratio)
+                })
+                .filter(|(_, w)| *w > 0.0)
+                .collect()
+        })
+        .collect()
+}
+
+/// Centre of mass in pixel-centre coordinates; the geometric centre for an
+/// empty image.
+fn centre_of_mass(pixels: &[f32], width: usize, height: usize) -> (f32, f32) {
+    let total: f32 = pixels.iter().sum();
+    if total <= 0.0 {
+        return (width as f32 / 2.0, height as f32 / 2.0);
+    }
+
+    let (mut cx, mut cy) = (0.0, 0.0);
+    for (i, p) in pixels.iter().enumerate() {
+        cx += p * ((i % width) as f32 + 0.5);
+        cy += p * ((i / width) as f32 + 0.5);
+    }
+
+    (cx / total, cy / total)
+}
+
+/// Where to put an image's origin so that `centre` lands mid-frame.
+fn offset(centre: f32) -> isize {
+    (MNIST_SIDE as f32 / 2.0 - centre).round() as isize
+}
+
+fn paste(frame: &mut [f32], src: &[f32], width: usize, height: usize, dx: isize, dy: isize) {
+    for y in 0..height {
+        for x in 0..width {
+            let (fx, fy) = (x as isize + dx, y as isize + dy);
+            if (0..MNIST_SIDE as isize).contains(&fx) && (0..MNIST_SIDE as isize).contains(&fy) {
+                frame[fy as usize * MNIST_SIDE + fx as usize] = src[y * width + x];
+            }
+        }
+    }
+}
+
+/// Shears the frame horizontally so the digit's principal axis is vertical.
+fn deskew(frame: &[f32]) -> Vec<f32> {
+    let side = MNIST_SIDE;
+    let (cx, cy) = centre_of_mass(frame, side, side);
+    let total: f32 = frame.iter().sum();
+
+    let (mut mu11, mut mu02) = (0.0, 0.0);
+    for (i, p) in frame.iter().enumerate() {
+        let (x, y) = ((i % side) as f32 + 0.5 - cx, (i / side) as f32 + 0.5 - cy);
+        mu11 += p * x * y;
+        mu02 += p * y * y;
+    }
+
+    if total <= 0.0 || mu02 <= f32::EPSILON {
+        return frame.to_vec();
+    }
+    let skew = mu11 / mu02;
+
+    (0..side * side)
+        .map(|i| {
+            let (x, y) = ((i % side) as f32, (i / side) as f32);
+            sample(frame, x + skew * (y + 0.5 - cy), y)
+        })
+        .collect()
+}
+
+/// Bilinear sample at pixel coordinates, zero outside the frame.
+fn sample(frame: &[f32], x: f32, y: f32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+
+    let at = |x: f32, y: f32| {
+        if x < 0.0 || y < 0.0 || x >= MNIST_SIDE as f32 || y >= MNIST_SIDE as f32 {
+            0.0
+        } else {
+            frame[y as usize * MNIST_SIDE + x as usize]
+        }
+    };
+
+    at(x0, y0) * (1.0 - fx) * (1.0 - fy)
+        + at(x0 + 1.0, y0) * fx * (1.0 - fy)
+        + at(x0, y0 + 1.0) * (1.0 - fx) * fy
+        + at(x0 + 1.0, y0 + 1.0) * fx * fy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas(width: usize, height: usize, ink: impl Fn(usize, usize) -> bool) -> GrayImage {
+        let pixels = (0..width * height)
+            .map(|i| if ink(i % width, i / width) { 255 } else { 0 })
+            .collect();
+        GrayImage::new(width, height, pixels).unwrap()
+    }
+
+    fn frame_bounds(frame: &[u8]) -> (usize, usize, usize, usize) {
+        let pixels: Vec<f32> = frame.iter().map(|p| *p as f32).collect();
+        bounding_box(&pixels, MNIST_SIDE).unwrap()
+    }
+
+    #[test]
+    fn blank_image_gives_blank_frame() {
+        let frame = preprocess(
+            &canvas(280, 280, |_, _| false),
+            &PreprocessOptions::default(),
+        );
+
+        assert_eq!(frame, vec![0; 784]);
+    }
+
+    #[test]
+    fn off_centre_digit_is_scaled_and_centred() {
+        // A 40×100 bar in the top-left corner of a large canvas.
+        let image = canvas(280, 280, |x, y| {
+            (10..50).contains(&x) && (5..105).contains(&y)
+        });
+
+        let frame = preprocess(&image, &PreprocessOptions::default());
+        let (x0, y0, x1, y1) = frame_bounds(&frame);
+        let pixels: Vec<f32> = frame.iter().map(|p| *p as f32).collect();
+        let (cx, cy) = centre_of_mass(&pixels, MNIST_SIDE, MNIST_SIDE);
+
+        assert_eq!(y1 - y0 + 1, BOX_SIDE);
+        assert_eq!(x1 - x0 + 1, 8);
+        assert!(
+            (cx - 14.0).abs() <= 0.5 && (cy - 14.0).abs() <= 0.5,
+            "{cx} {cy}"
+        );
+    }
+
+    #[test]
+    fn dark_ink_on_paper_is_inverted() {
+        let ink = |x: usize, y: usize| (40..60).contains(&x) && (20..80).contains(&y);
+        let light = canvas(100, 100, ink);
+        let photo = GrayImage::new(
+            100,
+            100,
+            // Grey paper, dark-grey ink.
+            light
+                .pixels
+                .iter()
+                .map(|p| if *p > 0 { 40 } else { 200 })
+                .collect(),
+        )
+        .unwrap();
+
+        let options = PreprocessOptions::default();
+
+        assert_eq!(preprocess(&photo, &options), preprocess(&light, &options));
+    }
+
+    #[test]
+    fn deskew_straightens_slanted_stroke() {
+        // A stroke leaning right: x moves left as y grows.
+        let image = canvas(100, 100, |x, y| {
+            (20..80).contains(&y) && (x as isize - (80 - y as isize / 2)).abs() < 4
+        });
+
+        let slanted = frame_bounds(&preprocess(&image, &PreprocessOptions::default()));
+        let straight = frame_bounds(&preprocess(
+            &image,
+            &PreprocessOptions {
+                deskew: true,
+                ..PreprocessOptions::default()
+            },
+        ));
+
+        assert!(
+            straight.2 - straight.0 < slanted.2 - slanted.0,
+            "{straight:?} vs {slanted:?}"
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_dimensions() {
+        assert!(matches!(
+            GrayImage::new(28, 28, vec![0; 100]),
+            Err(NNError::InvalidInput)
+        ));
+        assert!(GrayImage::new(0, 0, vec![]).is_err());
+        // The product wraps to 0 on 64-bit targets.
+        assert!(GrayImage::new(1 << 32, 1 << 32, vec![]).is_err());
+        assert!(GrayImage::new(MAX_IMAGE_SIDE + 1, 1, vec![0; MAX_IMAGE_SIDE + 1]).is_err());
+    }
+}
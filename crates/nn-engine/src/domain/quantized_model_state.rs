@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuantizationScheme {
+    /// One scale for the whole weight matrix.
+    PerTensor,
+    /// One scale per output neuron (matrix row).
+    PerChannel,
+}
+
+/// Symmetric int8 weights with `f32` scales; biases stay in `f32`.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct QuantizedModelState {
+    pub scheme: QuantizationScheme,
+    pub w1: Vec<i8>,         // 128 * 784
+    pub w1_scales: Vec<f32>, // 1 or 128
+    pub b1: Vec<f32>,        // 128
+    pub w2: Vec<i8>,         // 10 * 128
+    pub w2_scales: Vec<f32>, // 1 or 10
+    pub b2: Vec<f32>,        // 10
+    /// Scale of the uint8-quantized hidden activations, from calibration.
+    pub hidden_scale: f32,
+}
+
+impl QuantizedModelState {
+    /// Size of the raw tensors, ignoring container overhead.
+    pub fn size_bytes(&self) -> usize {
+        self.w1.len()
+            + self.w2.len()
+            + 4 * (self.w1_scales.len() + self.w2_scales.len() + self.b1.len() + self.b2.len() + 1)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QuantizationReport {
+    pub samples: usize,
+    pub float_accuracy: f32,
+    pub quantized_accuracy: f32,
+    /// Share of samples where both models predict the same digit.
+    pub agreement: f32,
+    pub mean_confidence_delta: f32,
+    pub float_size_bytes: usize,
+    pub quantized_size_bytes: usize,
+}
@@ -0,0 +1,141 @@
+use rand::Rng;
+use rand::seq::index;
+
+use crate::domain::{Prediction, TrainingSample, error::NNError};
+
+/// Rehearsal for online training: each new sample is trained in one
+/// mini-batch with `ratio` samples drawn from a stored MNIST subset, so a
+/// burst of similar user drawings can't pull the model away from the rest of
+/// the data. Updates leaving the accuracy on `validation` below
+/// `min_accuracy` are meant to be rolled back.
+#[derive(Debug, Clone)]
+pub struct ReplayBuffer {
+    rehearsal: Vec<TrainingSample>,
+    validation: Vec<TrainingSample>,
+    ratio: usize,
+    min_accuracy: Option<f32>,
+}
+
+impl ReplayBuffer {
+    pub fn new(rehearsal: Vec<TrainingSample>, ratio: usize) -> Self {
+        Self {
+            rehearsal,
+            validation: Vec::new(),
+            ratio,
+            min_accuracy: None,
+        }
+    }
+
+    /// Rejects updates that leave accuracy on `validation` below
+    /// `min_accuracy` (in `[0, 1]`).
+    pub fn with_validation(mut self, validation: Vec<TrainingSample>, min_accuracy: f32) -> Self {
+        self.validation = validation;
+        self.min_accuracy = Some(min_accuracy);
+        self
+    }
+
+    pub fn ratio(&self) -> usize {
+        self.ratio
+    }
+
+    pub fn validation(&self) -> &[TrainingSample] {
+        &self.validation
+    }
+
+    /// `None` when there is nothing to validate against.
+    pub fn min_accuracy(&self) -> Option<f32> {
+        self.min_accuracy.filter(|_| !self.validation.is_empty())
+    }
+
+    /// `sample` followed by up to `ratio` distinct rehearsal samples.
+    pub fn mix(&self, sample: TrainingSample, rng: &mut impl Rng) -> Vec<TrainingSample> {
+        let amount = self.ratio.min(self.rehearsal.len());
+
+        std::iter::once(sample)
+            .chain(
+                index::sample(rng, self.rehearsal.len(), amount)
+                    .into_iter()
+                    .map(|i| self.rehearsal[i].clone()),
+            )
+            .collect()
+    }
+
+    /// Share of `validation` the predictions got right; `predictions` are in
+    /// the same order as [`validation`](Self::validation).
+    pub fn accuracy(&self, predictions: &[Result<Prediction, NNError>]) -> f32 {
+        if self.validation.is_empty() {
+            return 1.0;
+        }
+
+        let correct = self
+            .validation
+            .iter()
+            .zip(predictions)
+            .filter(|(sample, prediction)| {
+                prediction
+                    .as_ref()
+                    .is_ok_and(|prediction| prediction.digit == sample.label)
+            })
+            .count();
+
+        correct as f32 / self.validation.len() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn samples(labels: impl IntoIterator<Item = u8>) -> Vec<TrainingSample> {
+        labels
+            .into_iter()
+            .map(|label| TrainingSample {
+                label,
+                pixels: vec![label; 784],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mix_puts_the_new_sample_first_and_draws_distinct_rehearsal_samples() {
+        let replay = ReplayBuffer::new(samples(0..10), 4);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let batch = replay.mix(samples([42]).remove(0), &mut rng);
+        assert_eq!(batch.len(), 5);
+        assert_eq!(batch[0].label, 42);
+
+        let mut drawn: Vec<u8> = batch[1..].iter().map(|s| s.label).collect();
+        drawn.sort();
+        drawn.dedup();
+        assert_eq!(drawn.len(), 4);
+        assert!(drawn.iter().all(|&label| label < 10));
+
+        // Never more than what's stored.
+        let small = ReplayBuffer::new(samples([1, 2]), 8);
+        assert_eq!(small.mix(samples([3]).remove(0), &mut rng).len(), 3);
+    }
+
+    #[test]
+    fn accuracy_counts_matching_predictions() {
+        let replay = ReplayBuffer::new(Vec::new(), 1).with_validation(samples([1, 2, 3, 4]), 0.5);
+        let prediction = |digit| {
+            Ok(Prediction {
+                digit,
+                confidence: 1.0,
+            })
+        };
+
+        let accuracy = replay.accuracy(&[
+            prediction(1),
+            prediction(2),
+            prediction(7),
+            Err(NNError::InvalidInput),
+        ]);
+        assert_eq!(accuracy, 0.5);
+        assert_eq!(replay.min_accuracy(), Some(0.5));
+        assert_eq!(ReplayBuffer::new(Vec::new(), 1).min_accuracy(), None);
+    }
+}
@@ -0,0 +1,378 @@
+//! Splitting an image of a multi-digit number into per-digit MNIST frames.
+//!
+//! Ink is grouped into 8-connected components; components stacked above one
+//! another (a detached stroke of a 5, the dot-like pieces of a broken line)
+//! are merged, specks are dropped, and components much wider than tall
+//! (touching digits) are cut at the emptiest column. Each resulting group is
+//! framed like a single digit by the preprocessing pipeline.
+
+use serde::Serialize;
+
+use crate::domain::Prediction;
+use crate::domain::error::NNError;
+use crate::domain::preprocessing::{
+    GrayImage, INK_THRESHOLD, PreprocessOptions, frame_digit, normalize,
+};
+
+/// Components smaller than this share of the largest one are noise.
+const MIN_AREA_RATIO: f32 = 0.05;
+/// Groups wider than this multiple of their height are split.
+const MAX_ASPECT: f32 = 1.25;
+/// Share of the narrower group's width two groups must overlap to merge.
+const MERGE_OVERLAP: f32 = 0.5;
+/// Most digits one image may hold; every one is framed and classified.
+pub const MAX_SEGMENTS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BoundingBox {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// One digit cut out of a larger image, already framed as 784 pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigitSegment {
+    pub bounds: BoundingBox,
+    pub pixels: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecognizedDigit {
+    pub digit: u8,
+    pub confidence: f32,
+    pub bounds: BoundingBox,
+}
+
+/// Digit string read left to right, with per-character confidences.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Recognition {
+    pub text: String,
+    pub digits: Vec<RecognizedDigit>,
+}
+
+impl Recognition {
+    pub fn new(segments: &[DigitSegment], predictions: Vec<Prediction>) -> Self {
+        let digits: Vec<RecognizedDigit> = segments
+            .iter()
+            .zip(predictions)
+            .map(|(segment, prediction)| RecognizedDigit {
+                digit: prediction.digit,
+                confidence: prediction.confidence,
+                bounds: segment.bounds,
+            })
+            .collect();
+
+        Self {
+            text: digits.iter().map(|d| (b'0' + d.digit) as char).collect(),
+            digits,
+        }
+    }
+}
+
+/// Segments `image` and classifies every digit with `predict`.
+pub fn recognize(
+    image: &GrayImage,
+    options: &PreprocessOptions,
+    mut predict: impl FnMut(&[u8]) -> Result<Prediction, NNError>,
+) -> Result<Recognition, NNError> {
+    let segments = segment_digits(image, options)?;
+    let predictions = segments
+        .iter()
+        .map(|segment| predict(&segment.pixels))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Recognition::new(&segments, predictions))
+}
+
+/// A set of ink pixels (indices into the image) and their bounding box.
+struct Group {
+    pixels: Vec<usize>,
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+}
+
+impl Group {
+    fn width(&self) -> usize {
+        self.x1 - self.x0 + 1
+    }
+
+    fn height(&self) -> usize {
+        self.y1 - self.y0 + 1
+    }
+
+    fn from_pixels(pixels: Vec<usize>, width: usize) -> Self {
+        let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+        for i in &pixels {
+            let (x, y) = (i % width, i / width);
+            (x0, y0, x1, y1) = (x0.min(x), y0.min(y), x1.max(x), y1.max(y));
+        }
+
+        Self {
+            pixels,
+            x0,
+            y0,
+            x1,
+            y1,
+        }
+    }
+
+    fn merge(mut self, other: Group) -> Self {
+        self.pixels.extend(other.pixels);
+        self.x0 = self.x0.min(other.x0);
+        self.y0 = self.y0.min(other.y0);
+        self.x1 = self.x1.max(other.x1);
+        self.y1 = self.y1.max(other.y1);
+        self
+    }
+
+    fn horizontal_overlap(&self, other: &Group) -> f32 {
+        let overlap = (self.x1.min(other.x1) + 1).saturating_sub(self.x0.max(other.x0));
+        overlap as f32 / self.width().min(other.width()) as f32
+    }
+}
+
+/// Finds the digits in `image`, left to right; more than [`MAX_SEGMENTS`]
+/// is an invalid input.
+pub fn segment_digits(
+    image: &GrayImage,
+    options: &PreprocessOptions,
+) -> Result<Vec<DigitSegment>, NNError> {
+    let width = image.width;
+    let pixels = normalize(image, options.detect_inversion);
+
+    let mut groups = connected_components(&pixels, width, image.height);
+
+    let largest = groups.iter().map(|g| g.pixels.len()).max().unwrap_or(0);
+    groups.retain(|g| g.pixels.len() as f32 >= largest as f32 * MIN_AREA_RATIO);
+    groups.sort_by_key(|g| g.x0);
+
+    let mut merged: Vec<Group> = Vec::new();
+    for group in groups {
+        match merged.last() {
+            Some(last) if last.horizontal_overlap(&group) >= MERGE_OVERLAP => {
+                let last = merged.pop().unwrap();
+                merged.push(last.merge(group));
+            }
+            _ => merged.push(group),
+        }
+    }
+
+    let groups: Vec<Group> = merged
+        .into_iter()
+        .flat_map(|g| split_wide(g, &pixels, width))
+        .collect();
+    if groups.len() > MAX_SEGMENTS {
+        return Err(NNError::InvalidInput);
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|group| {
+            let (w, h) = (group.width(), group.height());
+            let mut crop = vec![0.0; w * h];
+            for i in &group.pixels {
+                let (x, y) = (i % width - group.x0, i / width - group.y0);
+                crop[y * w + x] = pixels[*i];
+            }
+
+            DigitSegment {
+                bounds: BoundingBox {
+                    x: group.x0,
+                    y: group.y0,
+                    width: w,
+                    height: h,
+                },
+                pixels: frame_digit(&crop, w, options.deskew),
+            }
+        })
+        .collect())
+}
+
+fn connected_components(pixels: &[f32], width: usize, height: usize) -> Vec<Group> {
+    let mut seen = vec![false; pixels.len()];
+    let mut groups = Vec::new();
+
+    for start in 0..pixels.len() {
+        if seen[start] || pixels[start] <= INK_THRESHOLD {
+            continue;
+        }
+
+        seen[start] = true;
+        let mut stack = vec![start];
+        let mut component = Vec::new();
+
+        while let Some(i) = stack.pop() {
+            component.push(i);
+            let (x, y) = ((i % width) as isize, (i / width) as isize);
+
+            for (dx, dy) in [
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ] {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= width as isize || ny >= height as isize {
+                    continue;
+                }
+
+                let n = ny as usize * width + nx as usize;
+                if !seen[n] && pixels[n] > INK_THRESHOLD {
+                    seen[n] = true;
+                    stack.push(n);
+                }
+            }
+        }
+
+        groups.push(Group::from_pixels(component, width));
+    }
+
+    groups
+}
+
+/// Recursively cuts touching digits at the column with the least ink in
+/// the middle of the group.
+fn split_wide(group: Group, pixels: &[f32], width: usize) -> Vec<Group> {
+    if (group.width() as f32) <= group.height() as f32 * MAX_ASPECT {
+        return vec![group];
+    }
+
+    let mut columns = vec![0.0f32; group.width()];
+    for i in &group.pixels {
+        columns[i % width - group.x0] += pixels[*i];
+    }
+
+    let (lo, hi) = ((group.width() / 5).max(1), group.width() * 4 / 5);
+    let cut = (lo..hi)
+        .min_by(|a, b| columns[*a].partial_cmp(&columns[*b]).unwrap())
+        .map(|c| group.x0 + c)
+        .unwrap_or(group.x0 + group.width() / 2);
+
+    let (left, right): (Vec<usize>, Vec<usize>) =
+        group.pixels.into_iter().partition(|i| i % width < cut);
+
+    if left.is_empty() || right.is_empty() {
+        let mut all = left;
+        all.extend(right);
+        return vec![Group::from_pixels(all, width)];
+    }
+
+    [left, right]
+        .into_iter()
+        .flat_map(|part| split_wide(Group::from_pixels(part, width), pixels, width))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws filled rectangles `(x, y, w, h)` on a black canvas.
+    fn canvas(width: usize, height: usize, rects: &[(usize, usize, usize, usize)]) -> GrayImage {
+        let mut pixels = vec![0u8; width * height];
+        for (rx, ry, rw, rh) in rects {
+            for y in *ry..ry + rh {
+                for x in *rx..rx + rw {
+                    pixels[y * width + x] = 255;
+                }
+            }
+        }
+        GrayImage::new(width, height, pixels).unwrap()
+    }
+
+    #[test]
+    fn separate_strokes_become_ordered_segments() {
+        let image = canvas(
+            200,
+            60,
+            &[(150, 10, 8, 40), (20, 10, 8, 40), (85, 12, 8, 38)],
+        );
+
+        let segments = segment_digits(&image, &PreprocessOptions::default()).unwrap();
+
+        let xs: Vec<usize> = segments.iter().map(|s| s.bounds.x).collect();
+        assert_eq!(xs, vec![20, 85, 150]);
+        assert!(segments.iter().all(|s| s.pixels.len() == 784));
+    }
+
+    #[test]
+    fn stacked_pieces_are_merged_and_specks_dropped() {
+        // Two stacked bars form one digit; a 1×1 speck is noise.
+        let image = canvas(100, 80, &[(30, 5, 20, 6), (32, 20, 8, 50), (90, 70, 1, 1)]);
+
+        let segments = segment_digits(&image, &PreprocessOptions::default()).unwrap();
+
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].bounds.height, 65);
+    }
+
+    #[test]
+    fn touching_digits_are_split() {
+        // Two bars joined by a thin bridge at the bottom.
+        let image = canvas(
+            120,
+            60,
+            &[(10, 10, 10, 40), (60, 10, 10, 40), (10, 48, 60, 2)],
+        );
+
+        let segments = segment_digits(&image, &PreprocessOptions::default()).unwrap();
+
+        assert_eq!(segments.len(), 2);
+        assert!(segments[0].bounds.x < segments[1].bounds.x);
+    }
+
+    #[test]
+    fn too_many_segments_are_rejected() {
+        let bars: Vec<_> = (0..MAX_SEGMENTS + 1).map(|i| (i * 6, 0, 3, 10)).collect();
+        let image = canvas((MAX_SEGMENTS + 1) * 6, 10, &bars);
+
+        assert_eq!(
+            segment_digits(&image, &PreprocessOptions::default()),
+            Err(NNError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn blank_image_has_no_digits() {
+        let image = canvas(64, 32, &[]);
+
+        let recognition = recognize(&image, &PreprocessOptions::default(), |_| {
+            unreachable!("nothing to classify")
+        })
+        .unwrap();
+
+        assert_eq!(recognition.text, "");
+        assert!(recognition.digits.is_empty());
+    }
+
+    #[test]
+    fn recognition_joins_digits_left_to_right() {
+        let image = canvas(100, 40, &[(10, 5, 5, 30), (60, 5, 5, 30)]);
+        let segments = segment_digits(&image, &PreprocessOptions::default()).unwrap();
+
+        let recognition = Recognition::new(
+            &segments,
+            vec![
+                Prediction {
+                    digit: 4,
+                    confidence: 0.9,
+                },
+                Prediction {
+                    digit: 2,
+                    confidence: 0.8,
+                },
+            ],
+        );
+
+        assert_eq!(recognition.text, "42");
+        assert_eq!(recognition.digits[1].bounds.x, 60);
+    }
+}
@@ -1,7 +1,67 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domain::Prediction;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TrainingStepResult {
     pub loss: f32,
     pub correct: bool,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingSample {
+    pub label: u8,
+    pub pixels: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchTrainingResult {
+    /// Mean loss over the batch.
+    pub loss: f32,
+    pub correct: usize,
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsScope {
+    /// Running averages reported periodically inside an epoch.
+    Step,
+    /// Final averages for a whole epoch.
+    Epoch,
+}
+
+impl MetricsScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricsScope::Step => "step",
+            MetricsScope::Epoch => "epoch",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainingMetrics {
+    pub scope: MetricsScope,
+    pub epoch: usize,
+    /// Samples seen since the start of the run; monotonic across epochs.
+    pub step: usize,
+    /// Samples seen so far in the current epoch.
+    pub samples: usize,
+    pub loss: f32,
+    /// Accuracy in the `[0, 1]` range.
+    pub accuracy: f32,
+}
+
+/// A sample a user trained the live model on, kept for offline retraining.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmittedSample {
+    pub label: u8,
+    pub pixels: Vec<u8>,
+    /// Unix time in seconds.
+    pub timestamp: f64,
+    /// Version of the model the sample was submitted to.
+    pub model_version: String,
+    /// What that model predicted before training on the sample.
+    pub prediction: Prediction,
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{GrayImage, MNIST_SIDE, ModelState, error::NNError};
+
+/// Largest nearest-neighbour upscale of a weight grid; at 4 the default grid
+/// is already 3720×1864.
+pub const MAX_SCALE: usize = 4;
+
+/// What every layer of the network produced for one input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Activations {
+    /// The 128 hidden units after ReLU.
+    pub hidden: Vec<f32>,
+    /// The 10 output scores before softmax.
+    pub logits: Vec<f32>,
+    pub probabilities: Vec<f32>,
+}
+
+impl Activations {
+    /// Hidden units sorted by activation, strongest first.
+    pub fn strongest(&self) -> Vec<(usize, f32)> {
+        let mut units: Vec<(usize, f32)> = self.hidden.iter().copied().enumerate().collect();
+        units.sort_by(|a, b| b.1.total_cmp(&a.1));
+        units
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeightGridOptions {
+    /// Tiles per row; 128 hidden units make 8 rows of 16 by default.
+    pub columns: usize,
+    /// Black pixels between tiles, before scaling; at most 28.
+    pub padding: usize,
+    /// Nearest-neighbour upscale, up to [`MAX_SCALE`].
+    pub scale: usize,
+    /// Scale every tile by the largest weight of the layer instead of its
+    /// own, so tiles can be compared with each other.
+    pub shared_scale: bool,
+}
+
+impl Default for WeightGridOptions {
+    fn default() -> Self {
+        Self {
+            columns: 16,
+            padding: 1,
+            scale: 1,
+            shared_scale: false,
+        }
+    }
+}
+
+impl WeightGridOptions {
+    /// Rejects layouts that are empty or would render huge images.
+    pub fn validate(&self) -> Result<(), NNError> {
+        if self.columns == 0
+            || self.padding > MNIST_SIDE
+            || self.scale == 0
+            || self.scale > MAX_SCALE
+        {
+            return Err(NNError::InvalidInput);
+        }
+
+        Ok(())
+    }
+}
+
+/// The incoming `w1` weights of every hidden unit as a 28×28 image: zero is
+/// mid-gray, positive weights lighter, negative ones darker.
+pub fn weight_tiles(state: &ModelState, shared_scale: bool) -> Result<Vec<GrayImage>, NNError> {
+    let side = MNIST_SIDE * MNIST_SIDE;
+    if state.w1.is_empty() || !state.w1.len().is_multiple_of(side) {
+        return Err(NNError::SerializationError);
+    }
+
+    let magnitude = |weights: &[f32]| weights.iter().fold(0.0f32, |max, w| max.max(w.abs()));
+    let layer = magnitude(&state.w1);
+
+    state
+        .w1
+        .chunks(side)
+        .map(|row| {
+            let max = if shared_scale { layer } else { magnitude(row) };
+            let pixels = row
+                .iter()
+                .map(|w| {
+                    let w = if max > 0.0 { w / max } else { 0.0 };
+                    (127.5 + 127.5 * w).round().clamp(0.0, 255.0) as u8
+                })
+                .collect();
+
+            GrayImage::new(MNIST_SIDE, MNIST_SIDE, pixels)
+        })
+        .collect()
+}
+
+/// All [`weight_tiles`] laid out in one image.
+pub fn weight_grid(state: &ModelState, options: &WeightGridOptions) -> Result<GrayImage, NNError> {
+    options.validate()?;
+    let tiles = weight_tiles(state, options.shared_scale)?;
+
+    let columns = options.columns.min(tiles.len());
+    let rows = tiles.len().div_ceil(columns);
+    let step = MNIST_SIDE + options.padding;
+    let width = columns * step + options.padding;
+    let height = rows * step + options.padding;
+
+    let mut pixels = vec![0u8; width * height];
+    for (i, tile) in tiles.iter().enumerate() {
+        let (top, left) = (
+            options.padding + (i / columns) * step,
+            options.padding + (i % columns) * step,
+        );
+        for (y, row) in tile.pixels.chunks(MNIST_SIDE).enumerate() {
+            let start = (top + y) * width + left;
+            pixels[start..start + MNIST_SIDE].copy_from_slice(row);
+        }
+    }
+
+    Ok(upscale(
+        &GrayImage::new(width, height, pixels)?,
+        options.scale,
+    ))
+}
+
+/// Nearest-neighbour upscale by an integer factor.
+pub fn upscale(image: &GrayImage, scale: usize) -> GrayImage {
+    let width = image.width * scale;
+    let pixels = (0..image.height * scale)
+        .flat_map(|y| (0..width).map(move |x| image.pixels[(y / scale) * image.width + x / scale]))
+        .collect();
+
+    GrayImage {
+        width,
+        height: image.height * scale,
+        pixels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> ModelState {
+        // Unit `i` has every weight equal to `i - 64`.
+        ModelState {
+            w1: (0..128)
+                .flat_map(|i| std::iter::repeat_n(i as f32 - 64.0, 784))
+                .collect(),
+            b1: vec![0.0; 128],
+            w2: vec![0.0; 1280],
+            b2: vec![0.0; 10],
+        }
+    }
+
+    #[test]
+    fn tiles_map_zero_to_gray() {
+        let per_tile = weight_tiles(&state(), false).unwrap();
+        assert_eq!(per_tile.len(), 128);
+        assert_eq!(per_tile[0].pixels[0], 0);
+        assert_eq!(per_tile[64].pixels[0], 128);
+        assert_eq!(per_tile[127].pixels[0], 255);
+        assert_eq!(per_tile[65].pixels[0], 255);
+
+        let shared = weight_tiles(&state(), true).unwrap();
+        assert_eq!(shared[0].pixels[0], 0);
+        assert_eq!(shared[96].pixels[0], 191);
+    }
+
+    #[test]
+    fn grid_lays_out_tiles_with_padding_and_scale() {
+        let options = WeightGridOptions {
+            scale: 2,
+            ..WeightGridOptions::default()
+        };
+        let grid = weight_grid(&state(), &options).unwrap();
+
+        assert_eq!(grid.width, (16 * 29 + 1) * 2);
+        assert_eq!(grid.height, (8 * 29 + 1) * 2);
+        // Padding corner, then the first pixel of tiles 64 and 127.
+        assert_eq!(grid.pixels[0], 0);
+        assert_eq!(grid.pixels[(4 * 29 + 1) * 2 * grid.width + 2], 128);
+        let last = (7 * 29 + 1) * 2 * grid.width + (15 * 29 + 1) * 2;
+        assert_eq!(grid.pixels[last], 255);
+
+        let invalid = WeightGridOptions {
+            columns: 0,
+            ..WeightGridOptions::default()
+        };
+        assert_eq!(weight_grid(&state(), &invalid), Err(NNError::InvalidInput));
+    }
+
+    #[test]
+    fn strongest_units_come_first() {
+        let activations = Activations {
+            hidden: vec![0.0, 2.0, 0.5],
+            logits: vec![],
+            probabilities: vec![],
+        };
+
+        assert_eq!(activations.strongest(), vec![(1, 2.0), (2, 0.5), (0, 0.0)]);
+    }
+}
@@ -1,19 +1,54 @@
+#[cfg(feature = "blas")]
+extern crate blas_src;
+
 mod domain;
-pub use domain::ModelState;
+pub use domain::error::NNError;
+pub use domain::{
+    Activations, Attribution, AttributionMethod, BatchTrainingResult, BoundingBox, DigitSegment,
+    Feedback, FeedbackStatus, GrayImage, MAX_IMAGE_SIDE, MAX_SEGMENTS, MNIST_SIDE, MODEL_TENSORS,
+    MetricsScope, ModelInfo, ModelState, Prediction, PreprocessOptions, QuantizationReport,
+    QuantizationScheme, QuantizedModelState, Recognition, RecognizedDigit, ReplayBuffer,
+    SubmittedSample, TensorStats, TrainingMetrics, TrainingSample, TrainingStepResult,
+    WeightGridOptions, preprocess, recognize, segment_digits, upscale, weight_grid, weight_tiles,
+};
 pub mod port;
 
-
 mod adapter;
-pub use adapter::ndarray_engine::NdArrayEngine;
+#[cfg(feature = "server")]
+pub use adapter::async_ndarray_engine::{AsyncNdArrayEngine, LockWaitMetrics, LockWaitSnapshot};
+pub use adapter::compact::{CompactDType, export_compact, import_compact};
+#[cfg(feature = "server")]
+pub use adapter::feedback_queue::JsonLinesFeedbackQueue;
+#[cfg(feature = "server")]
+pub use adapter::file_repository::CompactModelRepository;
 #[cfg(feature = "server")]
 pub use adapter::file_repository::FileModelRepository;
 #[cfg(feature = "server")]
 pub use adapter::file_repository::JsonModelRepository;
 #[cfg(feature = "server")]
-pub use adapter::async_ndarray_engine::AsyncNdArrayEngine;
-
+pub use adapter::file_repository::NpzModelRepository;
+#[cfg(feature = "server")]
+pub use adapter::file_repository::OnnxModelRepository;
+#[cfg(feature = "server")]
+pub use adapter::file_repository::SafetensorsModelRepository;
+#[cfg(feature = "server")]
+pub use adapter::metrics_sink::{ConsoleMetricsSink, CsvMetricsSink, JsonLinesMetricsSink};
+pub use adapter::ndarray_engine::NdArrayEngine;
+#[cfg(feature = "server")]
+pub use adapter::npz::{export_npz, import_npz};
+pub use adapter::onnx::{export_onnx, import_onnx};
+#[cfg(feature = "server")]
+pub use adapter::parallel_trainer::ParallelBatchTrainer;
+#[cfg(feature = "server")]
+pub use adapter::png::encode_png;
+pub use adapter::quantized_engine::{QuantizedEngine, quantization_report};
+pub use adapter::safetensors::{export_safetensors, import_safetensors};
+#[cfg(feature = "server")]
+pub use adapter::sample_store::{JsonLinesSampleStore, export_training_csv, read_training_csv};
+#[cfg(feature = "server")]
+pub use adapter::tensorboard_metrics_sink::TensorBoardMetricsSink;
 
 #[cfg(feature = "server")]
 mod application;
 #[cfg(feature = "server")]
-pub use application::digit_classifier_service::DigitClassifierService;
\ No newline at end of file
+pub use application::digit_classifier_service::{DigitClassifierService, ModelStatus};
@@ -1,16 +1,48 @@
+use crate::domain::{
+    Activations, Attribution, AttributionMethod, BatchTrainingResult, ModelState, Prediction,
+    TrainingSample, TrainingStepResult, error::NNError,
+};
 use async_trait::async_trait;
-use crate::domain::{ModelState, Prediction, TrainingStepResult, error::NNError};
 
 #[async_trait]
 pub trait AsyncDigitPredictor {
     async fn predict(&self, pixels: &[u8]) -> Result<Prediction, NNError>;
 }
 
+#[async_trait]
+pub trait AsyncBatchPredictor {
+    async fn predict_batch(
+        &self,
+        batch: Vec<Vec<u8>>,
+    ) -> Result<Vec<Result<Prediction, NNError>>, NNError>;
+}
+
 #[async_trait]
 pub trait AsyncDigitTrainer {
     async fn train(&self, label: u8, pixels: &[u8]) -> Result<TrainingStepResult, NNError>;
 }
 
+#[async_trait]
+pub trait AsyncBatchTrainer {
+    async fn train_batch(&self, batch: Vec<TrainingSample>)
+    -> Result<BatchTrainingResult, NNError>;
+}
+
+#[async_trait]
+pub trait AsyncActivationExtractor {
+    async fn activations(&self, pixels: &[u8]) -> Result<Activations, NNError>;
+}
+
+#[async_trait]
+pub trait AsyncAttributionExplainer {
+    async fn explain(
+        &self,
+        pixels: &[u8],
+        class: u8,
+        method: AttributionMethod,
+    ) -> Result<Attribution, NNError>;
+}
+
 #[async_trait]
 pub trait AsyncModelStateExporter {
     async fn export_state(&self) -> Result<ModelState, NNError>;
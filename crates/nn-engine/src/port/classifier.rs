@@ -1,17 +1,43 @@
-use crate::domain::{ModelState, Prediction, TrainingStepResult, error::NNError};
+use crate::domain::{
+    Activations, Attribution, AttributionMethod, BatchTrainingResult, ModelState, Prediction,
+    TrainingSample, TrainingStepResult, error::NNError,
+};
 
 pub trait DigitPredictor {
     fn predict(&self, pixels: &[u8]) -> Result<Prediction, NNError>;
 }
 
+pub trait BatchPredictor {
+    /// Predicts every input in one pass; invalid inputs fail only their own slot.
+    fn predict_batch(&self, batch: &[Vec<u8>]) -> Vec<Result<Prediction, NNError>>;
+}
+
 pub trait DigitTrainer {
     fn train(&mut self, label: u8, pixels: &[u8]) -> Result<TrainingStepResult, NNError>;
 }
 
+pub trait BatchTrainer {
+    fn train_batch(&mut self, batch: &[TrainingSample]) -> Result<BatchTrainingResult, NNError>;
+}
+
 pub trait ModelStateExporter {
     fn export_state(&self) -> Result<ModelState, NNError>;
 }
 
 pub trait ModelStateImporter {
     fn import_state(&mut self, state: ModelState) -> Result<(), NNError>;
-}
\ No newline at end of file
+}
+
+pub trait AttributionExplainer {
+    /// How much each of the 784 pixels contributes to the score of `class`.
+    fn explain(
+        &self,
+        pixels: &[u8],
+        class: u8,
+        method: AttributionMethod,
+    ) -> Result<Attribution, NNError>;
+}
+
+pub trait ActivationExtractor {
+    fn activations(&self, pixels: &[u8]) -> Result<Activations, NNError>;
+}
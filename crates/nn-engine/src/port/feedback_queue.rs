@@ -0,0 +1,18 @@
+use crate::domain::{Feedback, FeedbackStatus, error::NNError};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait FeedbackQueue: Send + Sync {
+    /// Stores a pending sample, assigning its `id` and `submitted_at`.
+    async fn submit(&self, feedback: Feedback) -> Result<Feedback, NNError>;
+
+    /// Samples in submission order, optionally only those with `status`.
+    async fn list(&self, status: Option<FeedbackStatus>) -> Result<Vec<Feedback>, NNError>;
+
+    /// The sample with `id`, if any.
+    async fn get(&self, id: u64) -> Result<Option<Feedback>, NNError>;
+
+    /// Moves the pending samples among `ids` to `status` and returns them;
+    /// unknown or already resolved ids are skipped.
+    async fn resolve(&self, ids: &[u64], status: FeedbackStatus) -> Result<Vec<Feedback>, NNError>;
+}
@@ -0,0 +1,25 @@
+use crate::domain::{TrainingMetrics, error::NNError};
+
+pub trait MetricsSink {
+    fn record(&mut self, metrics: &TrainingMetrics) -> Result<(), NNError>;
+
+    fn flush(&mut self) -> Result<(), NNError> {
+        Ok(())
+    }
+}
+
+impl MetricsSink for Vec<Box<dyn MetricsSink>> {
+    fn record(&mut self, metrics: &TrainingMetrics) -> Result<(), NNError> {
+        for sink in self.iter_mut() {
+            sink.record(metrics)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), NNError> {
+        for sink in self.iter_mut() {
+            sink.flush()?;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,10 @@
-pub mod classifier;
 #[cfg(feature = "server")]
 pub mod async_classifier;
+pub mod classifier;
+#[cfg(feature = "server")]
+pub mod feedback_queue;
+pub mod metrics_sink;
 #[cfg(feature = "server")]
 pub mod model_repository;
+#[cfg(feature = "server")]
+pub mod sample_store;
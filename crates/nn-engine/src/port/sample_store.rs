@@ -0,0 +1,11 @@
+use crate::domain::{SubmittedSample, error::NNError};
+use async_trait::async_trait;
+
+/// Append-only dataset of user-submitted training samples.
+#[async_trait]
+pub trait SampleStore: Send + Sync {
+    async fn append(&self, sample: &SubmittedSample) -> Result<(), NNError>;
+
+    /// Every stored sample, oldest first.
+    async fn load(&self) -> Result<Vec<SubmittedSample>, NNError>;
+}
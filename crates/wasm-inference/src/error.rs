@@ -0,0 +1,32 @@
+use js_sys::{Error, Reflect};
+use wasm_bindgen::JsValue;
+
+use nn_engine::NNError;
+
+/// Builds a JS `Error` with `name = "NNError"` and a `kind` matching the
+/// `NNError` variant, so callers can branch on `err.kind`.
+pub(crate) fn js_error(kind: &str, message: &str) -> JsValue {
+    let error = Error::new(message);
+    error.set_name("NNError");
+
+    let _ = Reflect::set(&error, &"kind".into(), &kind.into());
+
+    error.into()
+}
+
+pub(crate) fn nn_error(error: NNError) -> JsValue {
+    let kind = match &error {
+        NNError::InvalidInput => "InvalidInput",
+        NNError::IoError(_) => "IoError",
+        NNError::SerializationError => "SerializationError",
+        NNError::PersistenceError => "PersistenceError",
+        NNError::InternalError => "InternalError",
+        NNError::UpdateRejected { .. } => "UpdateRejected",
+    };
+
+    js_error(kind, &error.to_string())
+}
+
+pub(crate) fn serialization_error(error: impl std::fmt::Display) -> JsValue {
+    js_error("SerializationError", &error.to_string())
+}
@@ -1 +1,2 @@
-pub mod wasm_api;
\ No newline at end of file
+mod error;
+pub mod wasm_api;
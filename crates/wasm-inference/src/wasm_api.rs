@@ -1,63 +1,186 @@
 use wasm_bindgen::prelude::*;
-use std::cell::RefCell;
+
+use serde::Serialize;
 
 use nn_engine::NdArrayEngine;
 use nn_engine::ModelState;
+use nn_engine::{Attribution, AttributionMethod};
+use nn_engine::{CompactDType, GrayImage, PreprocessOptions, export_compact, import_compact};
 use nn_engine::port::classifier::{
-    DigitPredictor, DigitTrainer, ModelStateImporter
+    AttributionExplainer, DigitPredictor, DigitTrainer, ModelStateExporter, ModelStateImporter
 };
 
-thread_local! {
-    static ENGINE: RefCell<NdArrayEngine> = RefCell::new(NdArrayEngine::new());
-}
+use crate::error::{js_error, nn_error, serialization_error};
 
 #[wasm_bindgen(start)]
 pub fn init() {
     console_error_panic_hook::set_once();
 }
 
+/// MNIST-style preprocessing of a grayscale canvas (`width * height` bytes,
+/// e.g. the red channel of `getImageData`) into the 784 pixels `predict`
+/// and `train` expect.
 #[wasm_bindgen]
-pub fn predict(pixels: Vec<u8>) -> JsValue {
-    ENGINE.with(|engine| {
-        let engine = engine.borrow();
-        let result = engine.predict(&pixels).unwrap();
+pub fn preprocess(
+    pixels: Vec<u8>,
+    width: usize,
+    height: usize,
+    deskew: Option<bool>,
+) -> Result<Vec<u8>, JsValue> {
+    let image = GrayImage::new(width, height, pixels).map_err(nn_error)?;
+    let options = PreprocessOptions {
+        deskew: deskew.unwrap_or(false),
+        ..PreprocessOptions::default()
+    };
 
-        serde_wasm_bindgen::to_value(&result).unwrap()
-    })
+    Ok(nn_engine::preprocess(&image, &options))
 }
 
-#[wasm_bindgen]
-pub fn train(label: u8, pixels: Vec<u8>) -> JsValue {
-    ENGINE.with(|engine| {
-        let mut engine = engine.borrow_mut();
-        let result = engine.train(label, &pixels).unwrap();
-
-        serde_wasm_bindgen::to_value(&result).unwrap()
-    })
+#[derive(Serialize)]
+struct Explanation {
+    #[serde(flatten)]
+    attribution: Attribution,
+    /// `values` scaled into `[-1, 1]`, for drawing.
+    heatmap: Vec<f32>,
 }
 
+/// One independent model; a page can hold several, e.g. the pretrained
+/// weights next to a copy being fine-tuned in the browser.
 #[wasm_bindgen]
-pub fn create_empty_model() {
-    ENGINE.with(|engine| {
-        *engine.borrow_mut() = NdArrayEngine::new();
-    });
+pub struct Classifier {
+    engine: NdArrayEngine,
+}
+
+impl Default for Classifier {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[wasm_bindgen]
-pub fn create_model_from_state(state: JsValue) -> Result<(), JsValue> {
-    let model_state: ModelState =
-        serde_wasm_bindgen::from_value(state)
-            .map_err(|e| JsValue::from_str(&format!("Deserialize error: {e}")))?;
+impl Classifier {
+    /// A freshly initialised, untrained model.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Classifier {
+        Classifier {
+            engine: NdArrayEngine::new(),
+        }
+    }
+
+    /// Builds a model from a `{ w1, b1, w2, b2 }` object.
+    pub fn from_state(state: JsValue) -> Result<Classifier, JsValue> {
+        let mut classifier = Classifier::new();
+        classifier.import_state(state)?;
+
+        Ok(classifier)
+    }
+
+    /// Builds a model from a compact binary checkpoint (`model convert --to
+    /// *.weights`), e.g. `new Uint8Array(await response.arrayBuffer())`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Classifier, JsValue> {
+        let mut classifier = Classifier::new();
+        classifier.import_bytes(bytes)?;
+
+        Ok(classifier)
+    }
+
+    /// An independent copy, so one model can be fine-tuned while the
+    /// original is kept for comparison.
+    #[wasm_bindgen(js_name = clone)]
+    pub fn duplicate(&self) -> Classifier {
+        Classifier {
+            engine: self.engine.clone(),
+        }
+    }
+
+    pub fn predict(&self, pixels: &[u8]) -> Result<JsValue, JsValue> {
+        let result = self.engine.predict(pixels).map_err(nn_error)?;
+
+        serde_wasm_bindgen::to_value(&result).map_err(serialization_error)
+    }
+
+    /// A 28×28 map `{ method, class, values, heatmap }` of how each pixel
+    /// contributes to `class` (the predicted digit by default). `method` is
+    /// e.g. `{ name: "integrated_gradients", steps: 32 }` or
+    /// `{ name: "occlusion", patch: 4, stride: 2 }`; saliency by default.
+    pub fn explain(
+        &self,
+        pixels: &[u8],
+        class: Option<u8>,
+        method: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let method: AttributionMethod = if method.is_undefined() || method.is_null() {
+            AttributionMethod::default()
+        } else {
+            serde_wasm_bindgen::from_value(method).map_err(serialization_error)?
+        };
+        let class = match class {
+            Some(class) => class,
+            None => self.engine.predict(pixels).map_err(nn_error)?.digit,
+        };
 
-    ENGINE.with(|engine| {
-        let mut new_engine = NdArrayEngine::new();
+        let attribution = self.engine.explain(pixels, class, method).map_err(nn_error)?;
+        let explanation = Explanation {
+            heatmap: attribution.normalized(),
+            attribution,
+        };
 
-        new_engine
-            .import_state(model_state)
-            .map_err(|e| JsValue::from_str(&format!("Import error: {e:?}")))?;
+        serde_wasm_bindgen::to_value(&explanation).map_err(serialization_error)
+    }
 
-        *engine.borrow_mut() = new_engine;
+    pub fn train(&mut self, label: u8, pixels: &[u8]) -> Result<JsValue, JsValue> {
+        let result = self.engine.train(label, pixels).map_err(nn_error)?;
+
+        serde_wasm_bindgen::to_value(&result).map_err(serialization_error)
+    }
+
+    pub fn import_state(&mut self, state: JsValue) -> Result<(), JsValue> {
+        let model_state: ModelState =
+            serde_wasm_bindgen::from_value(state).map_err(serialization_error)?;
+
+        self.replace_state(model_state)
+    }
+
+    pub fn import_bytes(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        let model_state = import_compact(bytes).map_err(nn_error)?;
+
+        self.replace_state(model_state)
+    }
+
+    /// Current weights as a `{ w1, b1, w2, b2 }` object.
+    pub fn export_state(&self) -> Result<JsValue, JsValue> {
+        let state = self.engine.export_state().map_err(nn_error)?;
+
+        serde_wasm_bindgen::to_value(&state).map_err(serialization_error)
+    }
+
+    /// Current weights as a compact binary checkpoint (`Uint8Array`) that
+    /// `from_bytes` and `model convert` read back.
+    /// `dtype` is `"f32"` (default), `"f16"` or `"int8"`.
+    pub fn export_bytes(&self, dtype: Option<String>) -> Result<Vec<u8>, JsValue> {
+        let dtype = match dtype.as_deref() {
+            None | Some("f32") => CompactDType::F32,
+            Some("f16") => CompactDType::F16,
+            Some("int8") => CompactDType::Int8,
+            Some(other) => {
+                return Err(js_error("InvalidInput", &format!("Unknown dtype: {other}")));
+            }
+        };
+
+        let state = self.engine.export_state().map_err(nn_error)?;
+
+        export_compact(&state, dtype).map_err(nn_error)
+    }
+}
+
+impl Classifier {
+    /// Imports into a fresh engine, so a rejected state leaves the current
+    /// weights untouched.
+    fn replace_state(&mut self, state: ModelState) -> Result<(), JsValue> {
+        let mut engine = NdArrayEngine::new();
+        engine.import_state(state).map_err(nn_error)?;
+        self.engine = engine;
 
         Ok(())
-    })
-}
\ No newline at end of file
+    }
+}
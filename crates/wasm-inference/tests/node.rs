@@ -0,0 +1,152 @@
+//! Run with `wasm-pack test --node crates/wasm-inference`.
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::Reflect;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+use wasm_inference::wasm_api::{Classifier, preprocess};
+
+fn kind(error: &JsValue) -> String {
+    Reflect::get(error, &"kind".into())
+        .unwrap()
+        .as_string()
+        .unwrap()
+}
+
+fn field(value: &JsValue, name: &str) -> JsValue {
+    Reflect::get(value, &name.into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn predict_returns_prediction_object() {
+    let prediction = Classifier::new().predict(&[0; 784]).unwrap();
+
+    let digit = field(&prediction, "digit").as_f64().unwrap();
+    let confidence = field(&prediction, "confidence").as_f64().unwrap();
+    assert!((0.0..10.0).contains(&digit));
+    assert!((0.0..=1.0).contains(&confidence));
+}
+
+#[wasm_bindgen_test]
+fn predict_rejects_wrong_canvas_size() {
+    let error = Classifier::new().predict(&[0; 100]).unwrap_err();
+
+    assert_eq!(kind(&error), "InvalidInput");
+    assert_eq!(field(&error, "name").as_string().unwrap(), "NNError");
+}
+
+#[wasm_bindgen_test]
+fn train_rejects_invalid_label() {
+    let mut classifier = Classifier::new();
+
+    let error = classifier.train(10, &[0; 784]).unwrap_err();
+
+    assert_eq!(kind(&error), "InvalidInput");
+    assert!(classifier.train(3, &[0; 784]).is_ok());
+}
+
+#[wasm_bindgen_test]
+fn create_model_rejects_malformed_state() {
+    let error = Classifier::from_state(JsValue::from_str("not a model")).err().unwrap();
+    assert_eq!(kind(&error), "SerializationError");
+
+    let error = Classifier::from_bytes(&[1, 2, 3]).err().unwrap();
+    assert_eq!(kind(&error), "SerializationError");
+}
+
+#[wasm_bindgen_test]
+fn import_rejects_short_bias_and_keeps_weights() {
+    let mut classifier = Classifier::new();
+    let before = field(&classifier.predict(&[90; 784]).unwrap(), "confidence");
+    let state = classifier.export_state().unwrap();
+    Reflect::set(&state, &"b1".into(), &js_sys::Array::of1(&0.into())).unwrap();
+
+    let error = classifier.import_state(state).unwrap_err();
+
+    assert_eq!(kind(&error), "SerializationError");
+    assert_eq!(field(&classifier.predict(&[90; 784]).unwrap(), "confidence"), before);
+}
+
+#[wasm_bindgen_test]
+fn import_rejects_non_finite_weights() {
+    let mut classifier = Classifier::new();
+    let state = classifier.export_state().unwrap();
+    let w2 = js_sys::Array::from(&field(&state, "w2"));
+    w2.set(0, JsValue::from_f64(f64::NAN));
+
+    let error = classifier.import_state(state).unwrap_err();
+
+    assert_eq!(kind(&error), "SerializationError");
+    assert_eq!(field(&error, "name").as_string().unwrap(), "NNError");
+    assert!(classifier.predict(&[90; 784]).is_ok());
+}
+
+#[wasm_bindgen_test]
+fn exported_state_round_trips() {
+    let mut classifier = Classifier::new();
+    classifier.train(7, &[200; 784]).unwrap();
+    let before = field(&classifier.predict(&[200; 784]).unwrap(), "confidence");
+
+    let from_state = Classifier::from_state(classifier.export_state().unwrap()).unwrap();
+    let from_bytes = Classifier::from_bytes(&classifier.export_bytes(None).unwrap()).unwrap();
+
+    assert_eq!(field(&from_state.predict(&[200; 784]).unwrap(), "confidence"), before);
+    assert_eq!(field(&from_bytes.predict(&[200; 784]).unwrap(), "confidence"), before);
+}
+
+#[wasm_bindgen_test]
+fn export_bytes_rejects_unknown_dtype() {
+    let classifier = Classifier::new();
+
+    let error = classifier.export_bytes(Some("bf16".into())).unwrap_err();
+
+    assert_eq!(kind(&error), "InvalidInput");
+    assert!(classifier.export_bytes(Some("f16".into())).unwrap().len() < 4 * 101_770);
+}
+
+#[wasm_bindgen_test]
+fn instances_do_not_share_weights() {
+    let pretrained = Classifier::new();
+    let mut fine_tuned = pretrained.duplicate();
+    let before = field(&pretrained.predict(&[120; 784]).unwrap(), "confidence");
+
+    for _ in 0..5 {
+        fine_tuned.train(4, &[120; 784]).unwrap();
+    }
+
+    assert_eq!(field(&pretrained.predict(&[120; 784]).unwrap(), "confidence"), before);
+    assert_ne!(field(&fine_tuned.predict(&[120; 784]).unwrap(), "confidence"), before);
+}
+
+#[wasm_bindgen_test]
+fn preprocess_returns_mnist_frame() {
+    let canvas: Vec<u8> = (0..280 * 280)
+        .map(|i| if (100..140).contains(&(i % 280)) { 255 } else { 0 })
+        .collect();
+
+    let pixels = preprocess(canvas, 280, 280, None).unwrap();
+    assert_eq!(pixels.len(), 784);
+    assert!(Classifier::new().predict(&pixels).is_ok());
+
+    let error = preprocess(vec![0; 10], 28, 28, None).unwrap_err();
+    assert_eq!(kind(&error), "InvalidInput");
+}
+
+#[wasm_bindgen_test]
+fn explain_returns_attribution_map() {
+    let classifier = Classifier::new();
+
+    let explanation = classifier.explain(&[128; 784], Some(3), JsValue::UNDEFINED).unwrap();
+    let values = js_sys::Array::from(&field(&explanation, "values"));
+    assert_eq!(values.length(), 784);
+    assert_eq!(field(&explanation, "class").as_f64(), Some(3.0));
+
+    let method = js_sys::Object::new();
+    Reflect::set(&method, &"name".into(), &"occlusion".into()).unwrap();
+    let explanation = classifier.explain(&[128; 784], None, method.into()).unwrap();
+    assert_eq!(js_sys::Array::from(&field(&explanation, "heatmap")).length(), 784);
+
+    let error = classifier.explain(&[0; 784], Some(10), JsValue::UNDEFINED).unwrap_err();
+    assert_eq!(kind(&error), "InvalidInput");
+}